@@ -145,30 +145,22 @@ async fn main() -> Result<()> {
     // Execute a real query (uncomment to test)
     // =========================================================================
 
-    let response = client
+    let result = client
         .from("users")
         .select("*")
         .limit(5)
-        .execute()
-        .await
-        .map_err(|e| supabase_client_rs::Error::config(format!("Request failed: {}", e)))?;
-
-    match response.status().is_success() {
-        true => {
-            let body = response.text().await.map_err(|e| {
-                supabase_client_rs::Error::config(format!("Failed to read response: {}", e))
-            })?;
-            let data: serde_json::Value = serde_json::from_str(&body)?;
-            println!("{}", serde_json::to_string_pretty(&data)?);
-        }
-        false => {
-            let response_status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            eprintln!("Error: {} - {}", response_status, error_text);
-        }
+        .execute_as::<serde_json::Value>()
+        .await;
+
+    match result {
+        Ok(data) => println!("{}", serde_json::to_string_pretty(&data)?),
+        Err(supabase_client_rs::Error::PostgREST {
+            message,
+            code,
+            status,
+            ..
+        }) => eprintln!("Error: {:?} {:?} - {}", status, code, message),
+        Err(e) => return Err(e),
     }
 
     println!("✓ All query examples compiled successfully!");
@@ -0,0 +1,720 @@
+//! Built-in GoTrue authentication client.
+//!
+//! Requires the `auth` feature. The client talks to the Supabase Auth API
+//! at [`SupabaseConfig::auth_url`] and keeps the current session in memory,
+//! persisting it to a [`SessionStorage`] when
+//! [`SupabaseConfig::persist_session`] is enabled.
+
+pub use crate::session_storage::{FileStorage, MemoryStorage};
+
+use crate::config::SupabaseConfig;
+use crate::error::{Error, Result};
+use crate::traits::{AuthProvider, SessionStorage, async_trait};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// How long before expiry the background task refreshes the session.
+///
+/// Capped at half the token lifetime for short-lived sessions.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// Minimum wait before a scheduled refresh, so sessions that are expired or
+/// very short-lived cannot make the background task refresh in a loop.
+const MIN_REFRESH_DELAY: Duration = Duration::from_secs(5);
+
+/// Upper bound for the retry delay after a failed refresh.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Number of auth events buffered for slow subscribers.
+const EVENT_CAPACITY: usize = 16;
+
+/// Callback invoked with the new access token whenever the session changes.
+pub(crate) type TokenListener = Box<dyn Fn(Option<&str>) + Send + Sync>;
+
+/// A user as returned by the Auth API.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct User {
+    /// Unique user identifier
+    pub id: String,
+    /// Audience the user belongs to
+    #[serde(default)]
+    pub aud: String,
+    /// Postgres role assigned to the user
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Email address
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Phone number
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// Timestamp of email confirmation
+    #[serde(default)]
+    pub email_confirmed_at: Option<String>,
+    /// Timestamp of last sign in
+    #[serde(default)]
+    pub last_sign_in_at: Option<String>,
+    /// Timestamp of creation
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Timestamp of last update
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Metadata managed by the server
+    #[serde(default)]
+    pub app_metadata: serde_json::Value,
+    /// Metadata managed by the user
+    #[serde(default)]
+    pub user_metadata: serde_json::Value,
+}
+
+/// An authenticated session.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Session {
+    /// The JWT to send as `Authorization: Bearer ...`
+    pub access_token: String,
+    /// Token type (usually `bearer`)
+    pub token_type: String,
+    /// Lifetime of the access token in seconds
+    pub expires_in: u64,
+    /// Unix timestamp (seconds) at which the access token expires
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    /// Token used to obtain a new session
+    pub refresh_token: String,
+    /// The signed-in user
+    pub user: User,
+}
+
+impl Session {
+    /// Fill in `expires_at` from `expires_in` when the server omitted it.
+    fn with_expiry(mut self) -> Self {
+        if self.expires_at.is_none() {
+            self.expires_at = Some(now_secs() + self.expires_in);
+        }
+        self
+    }
+
+    /// Time left before the session should be refreshed.
+    fn refresh_delay(&self) -> Duration {
+        let expires_at = self.expires_at.unwrap_or(now_secs() + self.expires_in);
+        let margin = EXPIRY_MARGIN.min(Duration::from_secs(self.expires_in / 2));
+        Duration::from_secs(expires_at.saturating_sub(now_secs())).saturating_sub(margin)
+    }
+}
+
+/// The result of a sign up.
+///
+/// `session` is `None` when the project requires email confirmation.
+#[derive(Debug, Clone)]
+pub struct AuthResponse {
+    /// The created user
+    pub user: User,
+    /// The session, if the user was signed in immediately
+    pub session: Option<Session>,
+}
+
+/// The kind of change reported by [`AuthClient::on_auth_state_change`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthChangeEvent {
+    /// A persisted session was restored
+    InitialSession,
+    /// A user signed in or signed up
+    SignedIn,
+    /// The user signed out or the session was revoked
+    SignedOut,
+    /// The access token was refreshed
+    TokenRefreshed,
+    /// The user's attributes were updated
+    UserUpdated,
+    /// The user signed in through a password recovery link
+    PasswordRecovery,
+}
+
+/// An auth state change together with the resulting session.
+#[derive(Debug, Clone)]
+pub struct AuthStateChange {
+    /// What happened
+    pub event: AuthChangeEvent,
+    /// The session after the change, `None` once signed out
+    pub session: Option<Session>,
+}
+
+/// Attributes accepted by [`AuthClient::update_user`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UserAttributes {
+    /// New email address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// New phone number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    /// New password
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Custom metadata to merge into `user_metadata`
+    #[serde(rename = "data", skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// Error payload returned by GoTrue.
+#[derive(Debug, Deserialize)]
+struct AuthErrorBody {
+    #[serde(default)]
+    msg: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    error_description: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl AuthErrorBody {
+    fn into_message(self) -> Option<String> {
+        self.msg
+            .or(self.message)
+            .or(self.error_description)
+            .or(self.error)
+    }
+}
+
+/// Client for the Supabase Auth (GoTrue) API.
+///
+/// Obtain one through `SupabaseClient::auth()`. When
+/// [`SupabaseConfig::auto_refresh_token`] is enabled, a background task
+/// refreshes the session shortly before it expires. Session changes are
+/// applied to the `Authorization` header of the owning `SupabaseClient`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use supabase_client_rs::SupabaseClient;
+///
+/// let client = SupabaseClient::new("https://your-project.supabase.co", "your-anon-key")?;
+/// let session = client
+///     .auth()
+///     .sign_in_with_password("alice@example.com", "secret")
+///     .await?;
+/// println!("Signed in as {}", session.user.id);
+/// # Ok(())
+/// # }
+/// ```
+pub struct AuthClient {
+    url: String,
+    http: reqwest::Client,
+    session: RwLock<Option<Session>>,
+    auto_refresh: bool,
+    token_listener: Option<TokenListener>,
+    refresh_task: Mutex<Option<JoinHandle<()>>>,
+    storage: Option<Arc<dyn SessionStorage>>,
+    storage_key: String,
+    events: broadcast::Sender<AuthStateChange>,
+    this: Weak<AuthClient>,
+}
+
+impl AuthClient {
+    /// Create a new auth client for the given configuration.
+    ///
+    /// `http` should carry the `apikey` header, as built by `SupabaseClient`.
+    pub fn new(config: &SupabaseConfig, http: reqwest::Client) -> Arc<Self> {
+        Self::build(config, http, None)
+    }
+
+    /// Create an auth client that reports access token changes to `listener`.
+    pub(crate) fn with_token_listener(
+        config: &SupabaseConfig,
+        http: reqwest::Client,
+        listener: TokenListener,
+    ) -> Arc<Self> {
+        Self::build(config, http, Some(listener))
+    }
+
+    fn build(
+        config: &SupabaseConfig,
+        http: reqwest::Client,
+        token_listener: Option<TokenListener>,
+    ) -> Arc<Self> {
+        Arc::new_cyclic(|this| Self {
+            url: config.auth_url(),
+            http,
+            session: RwLock::new(None),
+            auto_refresh: config.auto_refresh_token,
+            token_listener,
+            refresh_task: Mutex::new(None),
+            storage: config.persist_session.then(|| {
+                config
+                    .session_storage
+                    .clone()
+                    .unwrap_or_else(|| Arc::new(MemoryStorage::new()))
+            }),
+            storage_key: storage_key(&config.url),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            this: this.clone(),
+        })
+    }
+
+    /// Load the persisted session, refreshing it if it is about to expire.
+    ///
+    /// Call this at startup to stay signed in across process restarts.
+    /// Returns `None` when persistence is disabled or nothing was stored.
+    pub async fn restore_session(&self) -> Result<Option<Session>> {
+        let Some(ref storage) = self.storage else {
+            return Ok(None);
+        };
+        let Some(value) = storage.get(&self.storage_key).await? else {
+            return Ok(None);
+        };
+
+        let session: Session = match serde_json::from_str(&value) {
+            Ok(session) => session,
+            Err(e) => {
+                tracing::warn!("discarding unreadable persisted session: {}", e);
+                storage.remove(&self.storage_key).await?;
+                return Ok(None);
+            }
+        };
+
+        if !session.refresh_delay().is_zero() {
+            self.update_session(Some(session.clone()), AuthChangeEvent::InitialSession)
+                .await;
+            return Ok(Some(session));
+        }
+
+        match self.refresh_with_token(&session.refresh_token).await {
+            Ok(session) => Ok(Some(session)),
+            Err(Error::Auth(msg)) => {
+                tracing::warn!("persisted session could not be refreshed: {}", msg);
+                storage.remove(&self.storage_key).await?;
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Sign up a new user with email and password.
+    pub async fn sign_up(&self, email: &str, password: &str) -> Result<AuthResponse> {
+        let response = self
+            .http
+            .post(format!("{}/signup", self.url))
+            .json(&serde_json::json!({ "email": email, "password": password }))
+            .send()
+            .await?;
+        let body: serde_json::Value = Self::handle_response(response).await?;
+
+        // With autoconfirm the body is a session, otherwise a bare user.
+        let response = if body.get("access_token").is_some() {
+            let session: Session = serde_json::from_value::<Session>(body)?.with_expiry();
+            AuthResponse {
+                user: session.user.clone(),
+                session: Some(session),
+            }
+        } else {
+            AuthResponse {
+                user: serde_json::from_value(body)?,
+                session: None,
+            }
+        };
+
+        if let Some(ref session) = response.session {
+            self.update_session(Some(session.clone()), AuthChangeEvent::SignedIn)
+                .await;
+        }
+        Ok(response)
+    }
+
+    /// Sign in an existing user with email and password.
+    pub async fn sign_in_with_password(&self, email: &str, password: &str) -> Result<Session> {
+        let response = self
+            .http
+            .post(format!("{}/token", self.url))
+            .query(&[("grant_type", "password")])
+            .json(&serde_json::json!({ "email": email, "password": password }))
+            .send()
+            .await?;
+        let session = Self::handle_response::<Session>(response)
+            .await?
+            .with_expiry();
+
+        self.update_session(Some(session.clone()), AuthChangeEvent::SignedIn)
+            .await;
+        Ok(session)
+    }
+
+    /// Exchange a refresh token for a new session.
+    pub async fn refresh_with_token(&self, refresh_token: &str) -> Result<Session> {
+        let response = self
+            .http
+            .post(format!("{}/token", self.url))
+            .query(&[("grant_type", "refresh_token")])
+            .json(&serde_json::json!({ "refresh_token": refresh_token }))
+            .send()
+            .await?;
+        let session = Self::handle_response::<Session>(response)
+            .await?
+            .with_expiry();
+
+        self.update_session(Some(session.clone()), AuthChangeEvent::TokenRefreshed)
+            .await;
+        Ok(session)
+    }
+
+    /// Refresh the current session.
+    pub async fn refresh_session(&self) -> Result<Session> {
+        let session = self
+            .session()
+            .ok_or_else(|| Error::Auth("no active session".to_string()))?;
+        self.refresh_with_token(&session.refresh_token).await
+    }
+
+    /// Sign out, revoking the current session on the server.
+    ///
+    /// Does nothing when no user is signed in.
+    pub async fn sign_out(&self) -> Result<()> {
+        let Some(session) = self.session() else {
+            return Ok(());
+        };
+
+        let response = self
+            .http
+            .post(format!("{}/logout", self.url))
+            .bearer_auth(&session.access_token)
+            .send()
+            .await?;
+        self.set_session(None).await;
+
+        // An already revoked token still means the user is signed out.
+        let status = response.status();
+        if !status.is_success() && status != reqwest::StatusCode::UNAUTHORIZED {
+            Self::handle_response::<serde_json::Value>(response).await?;
+        }
+        Ok(())
+    }
+
+    /// Send a password recovery email to `email`.
+    pub async fn reset_password_for_email(&self, email: &str) -> Result<()> {
+        let response = self
+            .http
+            .post(format!("{}/recover", self.url))
+            .json(&serde_json::json!({ "email": email }))
+            .send()
+            .await?;
+        Self::handle_response::<serde_json::Value>(response).await?;
+        Ok(())
+    }
+
+    /// Sign in with the one-time token from a password recovery email.
+    pub async fn verify_recovery(&self, email: &str, token: &str) -> Result<Session> {
+        let response = self
+            .http
+            .post(format!("{}/verify", self.url))
+            .json(&serde_json::json!({ "type": "recovery", "email": email, "token": token }))
+            .send()
+            .await?;
+        let session = Self::handle_response::<Session>(response)
+            .await?
+            .with_expiry();
+
+        self.update_session(Some(session.clone()), AuthChangeEvent::PasswordRecovery)
+            .await;
+        Ok(session)
+    }
+
+    /// Update the signed-in user's attributes.
+    pub async fn update_user(&self, attributes: &UserAttributes) -> Result<User> {
+        let mut session = self
+            .session()
+            .ok_or_else(|| Error::Auth("no active session".to_string()))?;
+
+        let response = self
+            .http
+            .put(format!("{}/user", self.url))
+            .bearer_auth(&session.access_token)
+            .json(attributes)
+            .send()
+            .await?;
+        let user: User = Self::handle_response(response).await?;
+
+        session.user = user.clone();
+        self.update_session(Some(session), AuthChangeEvent::UserUpdated)
+            .await;
+        Ok(user)
+    }
+
+    /// Subscribe to auth state changes.
+    ///
+    /// Every change to the session held by this client is reported,
+    /// including background token refreshes.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # async fn example(client: supabase_client_rs::SupabaseClient) {
+    /// use supabase_client_rs::auth::AuthChangeEvent;
+    ///
+    /// let mut events = client.auth().on_auth_state_change();
+    /// tokio::spawn(async move {
+    ///     while let Ok(change) = events.recv().await {
+    ///         if change.event == AuthChangeEvent::SignedOut {
+    ///             println!("signed out");
+    ///         }
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub fn on_auth_state_change(&self) -> broadcast::Receiver<AuthStateChange> {
+        self.events.subscribe()
+    }
+
+    /// Fetch the signed-in user from the server.
+    ///
+    /// Returns `None` when no user is signed in.
+    pub async fn get_user(&self) -> Result<Option<User>> {
+        match self.session() {
+            Some(session) => self
+                .get_user_by_token(&session.access_token)
+                .await
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch the user that owns the given access token.
+    pub async fn get_user_by_token(&self, access_token: &str) -> Result<User> {
+        let response = self
+            .http
+            .get(format!("{}/user", self.url))
+            .bearer_auth(access_token)
+            .send()
+            .await?;
+        Self::handle_response(response).await
+    }
+
+    /// Get the current session without contacting the server.
+    pub fn session(&self) -> Option<Session> {
+        self.session
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Replace the current session.
+    ///
+    /// Persists the session, updates the owning client's `Authorization`
+    /// header and reschedules the automatic refresh. Emits `SignedIn` or
+    /// `SignedOut`.
+    pub async fn set_session(&self, session: Option<Session>) {
+        let event = match session {
+            Some(_) => AuthChangeEvent::SignedIn,
+            None => AuthChangeEvent::SignedOut,
+        };
+        self.update_session(session, event).await;
+    }
+
+    /// Apply a session change and notify subscribers with `event`.
+    async fn update_session(&self, session: Option<Session>, event: AuthChangeEvent) {
+        self.persist(session.as_ref()).await;
+
+        if let Some(ref listener) = self.token_listener {
+            listener(session.as_ref().map(|s| s.access_token.as_str()));
+        }
+        // Rescheduling aborts the running refresh task, so it must come
+        // last: this may be called from that very task.
+        *self.session.write().unwrap_or_else(|e| e.into_inner()) = session.clone();
+        self.schedule_refresh(session.as_ref());
+
+        // Sending only fails when nobody is subscribed.
+        let _ = self.events.send(AuthStateChange { event, session });
+    }
+
+    /// Write the session to storage. Failures are logged, not returned.
+    async fn persist(&self, session: Option<&Session>) {
+        let Some(ref storage) = self.storage else {
+            return;
+        };
+        let result = match session {
+            Some(session) => match serde_json::to_string(session) {
+                Ok(value) => storage.set(&self.storage_key, &value).await,
+                Err(e) => Err(e.into()),
+            },
+            None => storage.remove(&self.storage_key).await,
+        };
+        if let Err(e) = result {
+            tracing::warn!("failed to persist session: {}", e);
+        }
+    }
+
+    /// Replace the pending refresh task with one for `session`.
+    ///
+    /// Outside a Tokio runtime no task is spawned and the session must be
+    /// refreshed manually.
+    fn schedule_refresh(&self, session: Option<&Session>) {
+        let mut task = self.refresh_task.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(handle) = task.take() {
+            handle.abort();
+        }
+
+        let Some(session) = session.filter(|_| self.auto_refresh) else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::debug!("no Tokio runtime, automatic token refresh disabled");
+            return;
+        };
+
+        let this = self.this.clone();
+        let refresh_token = session.refresh_token.clone();
+        let delay = session.refresh_delay().max(MIN_REFRESH_DELAY);
+        *task = Some(runtime.spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            let mut retry_delay = Duration::from_secs(1);
+            loop {
+                let Some(auth) = this.upgrade() else {
+                    return;
+                };
+                match auth.refresh_with_token(&refresh_token).await {
+                    // The new session schedules its own refresh.
+                    Ok(_) => return,
+                    Err(Error::Auth(msg)) => {
+                        tracing::warn!("session refresh rejected, signing out: {}", msg);
+                        auth.set_session(None).await;
+                        return;
+                    }
+                    Err(e) => tracing::warn!("session refresh failed, retrying: {}", e),
+                }
+                drop(auth);
+
+                tokio::time::sleep(retry_delay).await;
+                retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+            }
+        }));
+    }
+
+    /// Decode a GoTrue response, turning non-2xx bodies into `Error::Auth`.
+    async fn handle_response<T: serde::de::DeserializeOwned>(
+        response: reqwest::Response,
+    ) -> Result<T> {
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<AuthErrorBody>(&body)
+                .ok()
+                .and_then(AuthErrorBody::into_message)
+                .unwrap_or_else(|| format!("request failed with status {}", status));
+            return Err(Error::Auth(message));
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+}
+
+#[async_trait]
+impl AuthProvider for AuthClient {
+    type User = User;
+    type Session = Session;
+
+    async fn sign_up_with_email(&self, email: &str, password: &str) -> Result<Session> {
+        self.sign_up(email, password)
+            .await?
+            .session
+            .ok_or_else(|| Error::Auth("email confirmation required".to_string()))
+    }
+
+    async fn sign_in_with_email(&self, email: &str, password: &str) -> Result<Session> {
+        self.sign_in_with_password(email, password).await
+    }
+
+    async fn sign_out(&self) -> Result<()> {
+        AuthClient::sign_out(self).await
+    }
+
+    async fn get_session(&self) -> Result<Option<Session>> {
+        Ok(self.session())
+    }
+
+    async fn get_user(&self) -> Result<Option<User>> {
+        AuthClient::get_user(self).await
+    }
+
+    async fn refresh_session(&self) -> Result<Session> {
+        AuthClient::refresh_session(self).await
+    }
+}
+
+impl Drop for AuthClient {
+    fn drop(&mut self) {
+        let task = self
+            .refresh_task
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(handle) = task.take() {
+            handle.abort();
+        }
+    }
+}
+
+impl std::fmt::Debug for AuthClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthClient")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Storage key for a project, e.g. `sb-xyzcompany-auth-token`.
+fn storage_key(url: &str) -> String {
+    let project_ref = url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.host_str()
+                .and_then(|h| h.split('.').next())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| "supabase".to_string());
+    format!("sb-{}-auth-token", project_ref)
+}
+
+/// Current Unix time in seconds.
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(expires_in: u64) -> Session {
+        Session {
+            access_token: "access".to_string(),
+            token_type: "bearer".to_string(),
+            expires_in,
+            expires_at: None,
+            refresh_token: "refresh".to_string(),
+            user: serde_json::from_value(serde_json::json!({ "id": "user-1" })).unwrap(),
+        }
+        .with_expiry()
+    }
+
+    #[test]
+    fn test_refresh_delay_margin() {
+        let delay = session(3600).refresh_delay();
+        assert!(delay <= Duration::from_secs(3540) && delay >= Duration::from_secs(3538));
+
+        // Short-lived sessions keep half their lifetime instead of refreshing at once
+        let delay = session(60).refresh_delay();
+        assert!(delay <= Duration::from_secs(30) && delay >= Duration::from_secs(28));
+
+        let expired = Session {
+            expires_at: Some(now_secs() - 10),
+            ..session(3600)
+        };
+        assert!(expired.refresh_delay().is_zero());
+    }
+}
@@ -0,0 +1,103 @@
+//! Generate Rust types from a Supabase project's PostgREST OpenAPI spec.
+//!
+//! ```bash
+//! # From a live project (defaults to SUPABASE_URL / SUPABASE_API_KEY)
+//! supabase-gen-types --url https://your-project.supabase.co --key your-key -o src/db.rs
+//!
+//! # From a saved spec
+//! supabase-gen-types --file openapi.json --derive
+//! ```
+
+use std::process::ExitCode;
+use supabase_client_rs::codegen::{CodegenOptions, generate};
+use supabase_client_rs::{Result, SupabaseClient, SupabaseConfig};
+
+const USAGE: &str = "\
+Usage: supabase-gen-types [OPTIONS]
+
+Options:
+  --url <URL>        Project URL (default: $SUPABASE_URL)
+  --key <KEY>        API key (default: $SUPABASE_API_KEY)
+  --schema <SCHEMA>  Database schema (default: public)
+  --file <PATH>      Read the OpenAPI spec from a file instead
+  --derive           Also derive SupabaseTable for tables and views
+  -o, --output <PATH>  Write to a file instead of stdout
+  -h, --help         Print this help";
+
+#[derive(Default)]
+struct Args {
+    url: Option<String>,
+    key: Option<String>,
+    schema: Option<String>,
+    file: Option<String>,
+    output: Option<String>,
+    derive: bool,
+}
+
+/// Parse the command line; `None` means help was requested.
+fn parse_args() -> std::result::Result<Option<Args>, String> {
+    let mut args = Args::default();
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(format!("missing value for {}", arg));
+        match arg.as_str() {
+            "--url" => args.url = Some(value()?),
+            "--key" => args.key = Some(value()?),
+            "--schema" => args.schema = Some(value()?),
+            "--file" => args.file = Some(value()?),
+            "-o" | "--output" => args.output = Some(value()?),
+            "--derive" => args.derive = true,
+            "-h" | "--help" => return Ok(None),
+            other => return Err(format!("unknown argument: {}", other)),
+        }
+    }
+    Ok(Some(args))
+}
+
+async fn run(args: Args) -> Result<()> {
+    let spec = match args.file {
+        Some(path) => tokio::fs::read_to_string(path).await?,
+        None => {
+            let url = args.url.or_else(|| std::env::var("SUPABASE_URL").ok());
+            let key = args.key.or_else(|| std::env::var("SUPABASE_API_KEY").ok());
+            let mut config = SupabaseConfig::new(url.unwrap_or_default(), key.unwrap_or_default());
+            if let Some(schema) = args.schema {
+                config = config.schema(schema);
+            }
+            SupabaseClient::with_config(config)?.openapi_spec().await?
+        }
+    };
+
+    let options = CodegenOptions {
+        derive_table: args.derive,
+    };
+    let source = generate(&spec, &options)?;
+    match args.output {
+        Some(path) => tokio::fs::write(path, source).await?,
+        None => print!("{}", source),
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Chunked bulk inserts and upserts.
+
+use crate::client::SupabaseClient;
+use crate::error::Result;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::ops::Range;
+
+/// Default number of chunks sent at the same time.
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// A bulk write that splits rows into chunks.
+///
+/// Created by [`SupabaseClient::bulk_insert`]. Each chunk is sent as its own
+/// request, so a failure only affects the rows of that chunk.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use supabase_client_rs::SupabaseClient;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = SupabaseClient::new("url", "key")?;
+/// # let rows: Vec<serde_json::Value> = vec![];
+/// let report = client
+///     .bulk_insert("events", &rows, 1000)
+///     .concurrency(8)
+///     .execute()
+///     .await;
+///
+/// for chunk in report.failed() {
+///     eprintln!("rows {:?} failed: {:?}", chunk.rows, chunk.result);
+///     // retry with &rows[chunk.rows.clone()]
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct BulkWrite<'a, T> {
+    client: SupabaseClient,
+    table: String,
+    rows: &'a [T],
+    chunk_size: usize,
+    concurrency: usize,
+    upsert: bool,
+    on_conflict: Option<String>,
+    ignore_duplicates: bool,
+}
+
+impl<'a, T: Serialize + Sync> BulkWrite<'a, T> {
+    pub(crate) fn new(
+        client: SupabaseClient,
+        table: &str,
+        rows: &'a [T],
+        chunk_size: usize,
+    ) -> Self {
+        Self {
+            client,
+            table: table.to_string(),
+            rows,
+            chunk_size: chunk_size.max(1),
+            concurrency: DEFAULT_CONCURRENCY,
+            upsert: false,
+            on_conflict: None,
+            ignore_duplicates: false,
+        }
+    }
+
+    /// Set how many chunks are sent at the same time (default: 4).
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Upsert the rows instead of inserting them.
+    pub fn upsert(mut self) -> Self {
+        self.upsert = true;
+        self
+    }
+
+    /// Resolve upsert conflicts on the given unique columns.
+    pub fn on_conflict(mut self, columns: impl Into<String>) -> Self {
+        self.on_conflict = Some(columns.into());
+        self
+    }
+
+    /// Skip conflicting rows during an upsert instead of merging them.
+    pub fn ignore_duplicates(mut self) -> Self {
+        self.ignore_duplicates = true;
+        self
+    }
+
+    /// Send all chunks and report the outcome of each.
+    ///
+    /// This never fails as a whole; inspect the report for failed chunks.
+    pub async fn execute(self) -> BulkReport {
+        let chunks = chunk_ranges(self.rows.len(), self.chunk_size);
+        let mut chunks: Vec<ChunkResult> = stream::iter(chunks)
+            .map(|rows| async {
+                let result = self.write_chunk(&self.rows[rows.clone()]).await;
+                ChunkResult { rows, result }
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+        chunks.sort_by_key(|chunk| chunk.rows.start);
+
+        BulkReport { chunks }
+    }
+
+    async fn write_chunk(&self, rows: &[T]) -> Result<()> {
+        let mut query = self.client.from(&self.table);
+        query = if self.upsert {
+            query.upsert_json(rows)?
+        } else {
+            query.insert_json(rows)?
+        };
+        if let Some(ref columns) = self.on_conflict {
+            query = query.on_conflict(columns);
+        }
+        if self.ignore_duplicates {
+            query = query.ignore_duplicates();
+        }
+        query.prefer("return=minimal").execute().await?;
+        Ok(())
+    }
+}
+
+impl<T> std::fmt::Debug for BulkWrite<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulkWrite")
+            .field("table", &self.table)
+            .field("rows", &self.rows.len())
+            .field("chunk_size", &self.chunk_size)
+            .field("concurrency", &self.concurrency)
+            .field("upsert", &self.upsert)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Split `len` rows into ranges of at most `chunk_size` rows.
+fn chunk_ranges(len: usize, chunk_size: usize) -> impl Iterator<Item = Range<usize>> {
+    (0..len)
+        .step_by(chunk_size)
+        .map(move |start| start..(start + chunk_size).min(len))
+}
+
+/// Outcome of a single chunk of a [`BulkWrite`].
+#[derive(Debug)]
+pub struct ChunkResult {
+    /// Offsets of the chunk's rows in the input slice
+    pub rows: Range<usize>,
+    /// Whether the chunk was written
+    pub result: Result<()>,
+}
+
+/// Outcome of a [`BulkWrite`], one entry per chunk in input order.
+#[derive(Debug)]
+pub struct BulkReport {
+    /// Per-chunk results
+    pub chunks: Vec<ChunkResult>,
+}
+
+impl BulkReport {
+    /// Whether every chunk was written.
+    pub fn is_success(&self) -> bool {
+        self.chunks.iter().all(|chunk| chunk.result.is_ok())
+    }
+
+    /// Chunks that failed.
+    pub fn failed(&self) -> impl Iterator<Item = &ChunkResult> {
+        self.chunks.iter().filter(|chunk| chunk.result.is_err())
+    }
+
+    /// Number of rows in successfully written chunks.
+    pub fn rows_written(&self) -> usize {
+        self.chunks
+            .iter()
+            .filter(|chunk| chunk.result.is_ok())
+            .map(|chunk| chunk.rows.len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_chunk_ranges() {
+        let ranges: Vec<_> = chunk_ranges(10, 4).collect();
+        assert_eq!(ranges, vec![0..4, 4..8, 8..10]);
+        assert_eq!(chunk_ranges(8, 4).collect::<Vec<_>>(), vec![0..4, 4..8]);
+        assert_eq!(chunk_ranges(0, 4).count(), 0);
+    }
+
+    #[test]
+    fn test_bulk_report() {
+        let report = BulkReport {
+            chunks: vec![
+                ChunkResult {
+                    rows: 0..4,
+                    result: Ok(()),
+                },
+                ChunkResult {
+                    rows: 4..8,
+                    result: Err(Error::config("rejected")),
+                },
+                ChunkResult {
+                    rows: 8..10,
+                    result: Ok(()),
+                },
+            ],
+        };
+
+        assert!(!report.is_success());
+        let failed: Vec<_> = report.failed().map(|chunk| chunk.rows.clone()).collect();
+        assert_eq!(failed, vec![4..8]);
+        assert_eq!(report.rows_written(), 6);
+    }
+}
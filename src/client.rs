@@ -1,20 +1,37 @@
 //! The main Supabase client.
 
+use crate::bulk::BulkWrite;
 use crate::config::SupabaseConfig;
 use crate::error::{Error, Result};
+use crate::query::QueryBuilder;
+use crate::rpc::RpcBuilder;
+use crate::traits::SupabaseTable;
 use postgrest::Postgrest;
 use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+#[cfg(feature = "auth")]
+use crate::auth::AuthClient;
+
+#[cfg(feature = "storage")]
+use crate::storage::StorageClient;
 
 #[cfg(feature = "realtime")]
-use supabase_realtime_rs::{RealtimeClient, RealtimeClientOptions};
+use std::sync::{Mutex, Weak};
+#[cfg(feature = "realtime")]
+use supabase_realtime_rs::{
+    RealtimeChannel, RealtimeChannelOptions, RealtimeClient, RealtimeClientOptions,
+};
 
 /// The main Supabase client.
 ///
 /// This client provides access to all Supabase services:
 /// - Database queries via PostgREST (`.from()`)
 /// - Realtime subscriptions (`.realtime()`) - requires `realtime` feature
-/// - Authentication (`.auth()`) - when community crate is available
-/// - Storage (`.storage()`) - when community crate is available
+/// - Authentication (`.auth()`) - requires `auth` feature
+/// - Storage (`.storage()`) - requires `storage` feature
 /// - Edge Functions (`.functions()`) - when community crate is available
 ///
 /// # Example
@@ -43,10 +60,140 @@ use supabase_realtime_rs::{RealtimeClient, RealtimeClientOptions};
 #[derive(Clone)]
 pub struct SupabaseClient {
     config: SupabaseConfig,
-    http: reqwest::Client,
-    postgrest: Postgrest,
+    transport: Arc<RwLock<Transport>>,
+    #[cfg(feature = "auth")]
+    auth: Arc<AuthClient>,
+    #[cfg(feature = "storage")]
+    storage: StorageClient,
     #[cfg(feature = "realtime")]
-    realtime: std::sync::Arc<RealtimeClient>,
+    realtime: Arc<RealtimeClient>,
+    #[cfg(feature = "realtime")]
+    realtime_channels: Arc<RealtimeChannels>,
+}
+
+/// Channels opened through `SupabaseClient::channel()`.
+///
+/// Tracked so access token changes can be pushed to every joined channel.
+/// The latest token is kept so channels subscribed later can be authorized
+/// right after they join.
+///
+/// Pushes take `sending` and always send the latest token, so pushes that
+/// finish out of order still leave every channel with the newest token.
+#[cfg(feature = "realtime")]
+#[derive(Default)]
+struct RealtimeChannels {
+    channels: Mutex<Vec<Weak<RealtimeChannel>>>,
+    token: Mutex<Option<String>>,
+    sending: tokio::sync::Mutex<()>,
+}
+
+#[cfg(feature = "realtime")]
+impl RealtimeChannels {
+    fn register(&self, channel: &Arc<RealtimeChannel>) {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels.retain(|c| c.strong_count() > 0);
+        if !channels.iter().any(|c| c.as_ptr() == Arc::as_ptr(channel)) {
+            channels.push(Arc::downgrade(channel));
+        }
+    }
+
+    fn set_token(&self, token: &str) {
+        *self.token.lock().unwrap_or_else(|e| e.into_inner()) = Some(token.to_string());
+    }
+
+    fn token(&self) -> Option<String> {
+        self.token.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Send the latest token to every joined channel.
+    async fn push_token(&self) -> Result<()> {
+        let _sending = self.sending.lock().await;
+        let Some(token) = self.token() else {
+            return Ok(());
+        };
+        let channels: Vec<_> = {
+            let channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+            channels.iter().filter_map(Weak::upgrade).collect()
+        };
+        for channel in channels {
+            if channel.was_joined().await {
+                Self::send_token(&channel, &token).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Join `channel`, then authorize it with the latest token.
+    ///
+    /// The realtime client joins with the token it was created with, so a
+    /// session started afterwards only applies once it is pushed.
+    async fn subscribe(&self, channel: &Arc<RealtimeChannel>) -> Result<()> {
+        self.register(channel);
+        channel.subscribe().await?;
+        let _sending = self.sending.lock().await;
+        if let Some(token) = self.token() {
+            Self::send_token(channel, &token).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_token(channel: &Arc<RealtimeChannel>, token: &str) -> Result<()> {
+        channel
+            .push("access_token", serde_json::json!({ "access_token": token }))
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+/// HTTP and PostgREST clients sharing the same auth headers.
+///
+/// Held behind a lock so the Authorization header can be swapped when the
+/// auth session changes.
+pub(crate) struct Transport {
+    pub(crate) http: reqwest::Client,
+    postgrest: Postgrest,
+}
+
+impl Transport {
+    /// Build the clients, authorizing with `token` or falling back to the API key.
+    fn build(config: &SupabaseConfig, token: Option<&str>) -> Result<Self> {
+        // Build default headers
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "apikey",
+            HeaderValue::from_str(&config.api_key).map_err(|e| Error::config(e.to_string()))?,
+        );
+
+        // Add Authorization header
+        let auth_value = format!("Bearer {}", token.unwrap_or(&config.api_key));
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&auth_value).map_err(|e| Error::config(e.to_string()))?,
+        );
+
+        // Add custom headers
+        for (key, value) in &config.headers {
+            let name = HeaderName::try_from(key.as_str())
+                .map_err(|e| Error::config(format!("invalid header name: {}", e)))?;
+            let val = HeaderValue::from_str(value)
+                .map_err(|e| Error::config(format!("invalid header value: {}", e)))?;
+            headers.insert(name, val);
+        }
+
+        // Build HTTP client
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(config.timeout)
+            .build()?;
+
+        // Build PostgREST client
+        let postgrest = Postgrest::new(config.rest_url())
+            .insert_header("apikey", &config.api_key)
+            .insert_header("Authorization", &auth_value);
+
+        Ok(Self { http, postgrest })
+    }
 }
 
 impl SupabaseClient {
@@ -97,63 +244,79 @@ impl SupabaseClient {
             return Err(Error::config("API key is required"));
         }
 
-        // Build default headers
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "apikey",
-            HeaderValue::from_str(&config.api_key).map_err(|e| Error::config(e.to_string()))?,
-        );
-
-        // Add Authorization header
-        let auth_value = if let Some(ref jwt) = config.jwt {
-            format!("Bearer {}", jwt)
-        } else {
-            format!("Bearer {}", config.api_key)
-        };
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&auth_value).map_err(|e| Error::config(e.to_string()))?,
-        );
-
-        // Add custom headers
-        for (key, value) in &config.headers {
-            let name = HeaderName::try_from(key.as_str())
-                .map_err(|e| Error::config(format!("invalid header name: {}", e)))?;
-            let val = HeaderValue::from_str(value)
-                .map_err(|e| Error::config(format!("invalid header value: {}", e)))?;
-            headers.insert(name, val);
-        }
-
-        // Build HTTP client
-        let http = reqwest::Client::builder()
-            .default_headers(headers.clone())
-            .timeout(config.timeout)
-            .build()?;
-
-        // Build PostgREST client
-        let postgrest = Postgrest::new(config.rest_url())
-            .insert_header("apikey", &config.api_key)
-            .insert_header("Authorization", &auth_value);
+        let transport = Transport::build(&config, config.jwt.as_deref())?;
+        let transport = Arc::new(RwLock::new(transport));
 
         // Build Realtime client if feature is enabled
         #[cfg(feature = "realtime")]
         let realtime = {
             let realtime_client = RealtimeClient::new(
-                &config.realtime_url(),
+                config.realtime_url(),
                 RealtimeClientOptions {
                     api_key: config.api_key.clone(),
+                    access_token: config.jwt.clone(),
                     ..Default::default()
                 },
             )?;
-            std::sync::Arc::new(realtime_client)
+            Arc::new(realtime_client)
         };
+        #[cfg(feature = "realtime")]
+        let realtime_channels = Arc::new(RealtimeChannels::default());
+
+        // Build Auth client if feature is enabled. Session changes swap the
+        // Authorization header used by the shared transport.
+        #[cfg(feature = "auth")]
+        let auth = {
+            let http = transport
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .http
+                .clone();
+            let shared = Arc::clone(&transport);
+            let transport_config = config.clone();
+            #[cfg(feature = "realtime")]
+            let channels = Arc::clone(&realtime_channels);
+            AuthClient::with_token_listener(
+                &config,
+                http,
+                Box::new(move |token| {
+                    let token = token.or(transport_config.jwt.as_deref());
+                    match Transport::build(&transport_config, token) {
+                        Ok(rebuilt) => *shared.write().unwrap_or_else(|e| e.into_inner()) = rebuilt,
+                        Err(e) => tracing::warn!("failed to apply new access token: {}", e),
+                    }
+
+                    // Keep realtime authorization in line with REST
+                    #[cfg(feature = "realtime")]
+                    {
+                        channels.set_token(token.unwrap_or(&transport_config.api_key));
+                        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                            let channels = Arc::clone(&channels);
+                            runtime.spawn(async move {
+                                if let Err(e) = channels.push_token().await {
+                                    tracing::warn!("failed to update realtime access token: {}", e);
+                                }
+                            });
+                        }
+                    }
+                }),
+            )
+        };
+
+        #[cfg(feature = "storage")]
+        let storage = StorageClient::new(config.storage_url(), Arc::clone(&transport));
 
         Ok(Self {
             config: config.clone(),
-            http,
-            postgrest,
+            transport,
+            #[cfg(feature = "auth")]
+            auth,
+            #[cfg(feature = "storage")]
+            storage,
             #[cfg(feature = "realtime")]
             realtime,
+            #[cfg(feature = "realtime")]
+            realtime_channels,
         })
     }
 
@@ -195,8 +358,46 @@ impl SupabaseClient {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn from(&self, table: &str) -> postgrest::Builder {
-        self.postgrest.from(table)
+    pub fn from(&self, table: &str) -> QueryBuilder {
+        QueryBuilder::new(self.postgrest().from(table), self.http())
+    }
+
+    /// Start a query on the table of `T`.
+    ///
+    /// Use [`QueryBuilder::select_all`] to select the columns of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use supabase_client_rs::SupabaseTable;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct User {
+    ///     id: i64,
+    ///     email: String,
+    /// }
+    ///
+    /// // Or `#[derive(SupabaseTable)]` with the `derive` feature
+    /// impl SupabaseTable for User {
+    ///     const TABLE: &'static str = "users";
+    ///     const COLUMNS: &'static [&'static str] = &["id", "email"];
+    ///     const PRIMARY_KEY: &'static [&'static str] = &["id"];
+    /// }
+    ///
+    /// let users: Vec<User> = client
+    ///     .table::<User>()
+    ///     .select_all()
+    ///     .eq("email", "alice@example.com")
+    ///     .execute_as()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn table<T: SupabaseTable>(&self) -> QueryBuilder {
+        self.from(T::TABLE).with_columns(T::COLUMNS)
     }
 
     /// Execute a stored procedure (RPC).
@@ -214,8 +415,108 @@ impl SupabaseClient {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn rpc(&self, function: &str, params: &str) -> postgrest::Builder {
-        self.postgrest.rpc(function, params)
+    pub fn rpc(&self, function: &str, params: &str) -> QueryBuilder {
+        QueryBuilder::new(self.postgrest().rpc(function, params), self.http())
+    }
+
+    /// Insert many rows in chunks of `chunk_size`.
+    ///
+    /// Chunks are sent concurrently and reported individually, so a partial
+    /// failure can be retried without resending the whole batch. Call
+    /// [`BulkWrite::upsert`] to upsert instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// # let rows: Vec<serde_json::Value> = vec![];
+    /// let report = client.bulk_insert("events", &rows, 500).execute().await;
+    /// println!("{} rows written", report.rows_written());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bulk_insert<'a, T: Serialize + Sync>(
+        &self,
+        table: &str,
+        rows: &'a [T],
+        chunk_size: usize,
+    ) -> BulkWrite<'a, T> {
+        BulkWrite::new(self.clone(), table, rows, chunk_size)
+    }
+
+    /// Call a stored procedure with typed arguments and return value.
+    ///
+    /// `args` must serialize to an object whose keys are the argument names
+    /// (use `&()` for no arguments). Set-returning functions return a list,
+    /// so use `Vec<Row>` as `R` and filter the rows on the builder.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use supabase_client_rs::Filter;
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Search<'a> {
+    ///     query: &'a str,
+    /// }
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Hit {
+    ///     id: i64,
+    ///     score: f64,
+    /// }
+    ///
+    /// let hits = client
+    ///     .rpc_typed::<_, Vec<Hit>>("search_posts", &Search { query: "rust" })?
+    ///     .get()
+    ///     .filter(Filter::gt("score", 0.5))
+    ///     .order("score.desc")
+    ///     .limit(10)
+    ///     .execute()
+    ///     .await?;
+    ///
+    /// let total: i64 = client.rpc_typed::<_, i64>("count_posts", &())?.execute().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rpc_typed<A: Serialize, R: DeserializeOwned>(
+        &self,
+        function: &str,
+        args: &A,
+    ) -> Result<RpcBuilder<R>> {
+        RpcBuilder::new(self.from(&format!("rpc/{}", function)), args)
+    }
+
+    /// Query a different schema than the configured one.
+    ///
+    /// Returns a client whose `from()` and `rpc()` target `schema` through
+    /// the `Accept-Profile`/`Content-Profile` headers. The returned client
+    /// shares auth state with this one.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// let events = client
+    ///     .schema("analytics")
+    ///     .from("events")
+    ///     .select("*")
+    ///     .execute()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn schema(&self, schema: impl Into<String>) -> Self {
+        let mut client = self.clone();
+        client.config.schema = schema.into();
+        client
     }
 
     /// Get the configuration.
@@ -225,16 +526,25 @@ impl SupabaseClient {
 
     /// Get the underlying HTTP client.
     ///
-    /// Useful for making custom requests to Supabase APIs.
-    pub fn http(&self) -> &reqwest::Client {
-        &self.http
+    /// Useful for making custom requests to Supabase APIs. The returned client
+    /// carries the Authorization header current at the time of the call.
+    pub fn http(&self) -> reqwest::Client {
+        self.transport().http.clone()
     }
 
     /// Get the PostgREST client.
     ///
     /// Use this if you need direct access to the PostgREST client.
-    pub fn postgrest(&self) -> &Postgrest {
-        &self.postgrest
+    /// The configured schema is already applied.
+    pub fn postgrest(&self) -> Postgrest {
+        self.transport()
+            .postgrest
+            .clone()
+            .schema(&self.config.schema)
+    }
+
+    fn transport(&self) -> RwLockReadGuard<'_, Transport> {
+        self.transport.read().unwrap_or_else(|e| e.into_inner())
     }
 
     /// Set a JWT for authenticated requests.
@@ -259,29 +569,66 @@ impl SupabaseClient {
         Self::with_config(new_config)
     }
 
-    /*
     // =========================================================================
-    // Future: Auth, Storage, Functions, Realtime
-    // These will be enabled when community crates are available
+    // Auth - Built-in GoTrue client
     // =========================================================================
-    /// Access the Auth client.
+
+    /// Get the Auth client.
+    ///
+    /// Requires the `auth` feature to be enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(feature = "auth")]
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// let session = client
+    ///     .auth()
+    ///     .sign_in_with_password("alice@example.com", "secret")
+    ///     .await?;
     ///
-    /// **Note:** This requires an auth provider to be set up.
-    /// See the `supabase-auth-rs` crate (when available).
+    /// // Use the access token for RLS-protected queries
+    /// let user_client = client.with_jwt(&session.access_token)?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[cfg(feature = "auth")]
-    pub fn auth(&self) -> &dyn crate::traits::AuthProvider {
-        todo!("Auth provider not yet implemented - contribute at supabase-auth-rs!")
+    pub fn auth(&self) -> &AuthClient {
+        &self.auth
     }
 
-    /// Access the Storage client.
+    // =========================================================================
+    // Storage - Built-in Storage client
+    // =========================================================================
+
+    /// Get the Storage client.
+    ///
+    /// Requires the `storage` feature to be enabled. Requests use the same
+    /// auth headers as database queries.
     ///
-    /// **Note:** This requires a storage provider to be set up.
-    /// See the `supabase-storage-rs` crate (when available).
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// let files = client.storage().list("avatars", None).await?;
+    /// let url = client.storage().get_public_url("avatars", "alice.png");
+    /// # Ok(())
+    /// # }
+    /// ```
     #[cfg(feature = "storage")]
-    pub fn storage(&self) -> &dyn crate::traits::StorageProvider {
-        todo!("Storage provider not yet implemented - contribute at supabase-storage-rs!")
+    pub fn storage(&self) -> &StorageClient {
+        &self.storage
     }
 
+    /*
+    // =========================================================================
+    // Future: Functions
+    // This will be enabled when a community crate is available
+    // =========================================================================
     /// Access the Functions client.
     ///
     /// **Note:** This requires a functions provider to be set up.
@@ -333,6 +680,88 @@ impl SupabaseClient {
         &self.realtime
     }
 
+    /// Create (or reuse) a Realtime channel that follows auth changes.
+    ///
+    /// Channels joined with the client's JWT authorize `postgres_changes`
+    /// under RLS like REST requests do. Unlike channels created directly
+    /// through [`realtime()`](Self::realtime), channels created here receive
+    /// refreshed access tokens when the auth session changes.
+    ///
+    /// Join the channel with [`subscribe()`](Self::subscribe) rather than
+    /// `RealtimeChannel::subscribe`, so it is authorized as the user who is
+    /// signed in now, not with the token the client was created with.
+    ///
+    /// # Reconnects
+    ///
+    /// When the socket drops, the realtime client reconnects and rejoins
+    /// channels on its own, with the token it was created with. That rejoin
+    /// is not visible through its API, so the current token is **not** sent
+    /// again: after a reconnect the channel runs as anon (or as the JWT the
+    /// client was created with) until the next token change. Long-running
+    /// subscribers should call [`set_realtime_auth()`](Self::set_realtime_auth)
+    /// once they know the socket reconnected.
+    ///
+    /// Requires the `realtime` feature to be enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # #[cfg(all(feature = "realtime", feature = "auth"))]
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # use supabase_realtime_rs::RealtimeChannelOptions;
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// client
+    ///     .auth()
+    ///     .sign_in_with_password("alice@example.com", "secret")
+    ///     .await?;
+    ///
+    /// client.realtime().connect().await?;
+    /// let channel = client.channel("db-changes", RealtimeChannelOptions::default()).await;
+    /// client.subscribe(&channel).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "realtime")]
+    pub async fn channel(
+        &self,
+        topic: &str,
+        options: RealtimeChannelOptions,
+    ) -> Arc<RealtimeChannel> {
+        let channel = self.realtime.channel(topic, options).await;
+        self.realtime_channels.register(&channel);
+        channel
+    }
+
+    /// Join `channel` and authorize it with the current access token.
+    ///
+    /// The token of the signed-in user (or the last one passed to
+    /// [`set_realtime_auth()`](Self::set_realtime_auth)) is sent right after
+    /// the join, and the channel keeps receiving refreshed tokens.
+    ///
+    /// The token is not sent again when the realtime client rejoins after a
+    /// reconnect; see [`channel()`](Self::channel#reconnects).
+    ///
+    /// Requires the `realtime` feature to be enabled.
+    #[cfg(feature = "realtime")]
+    pub async fn subscribe(&self, channel: &Arc<RealtimeChannel>) -> Result<()> {
+        self.realtime_channels.subscribe(channel).await
+    }
+
+    /// Push an access token to every channel created with
+    /// [`channel()`](Self::channel).
+    ///
+    /// This happens automatically on auth session changes; call it when
+    /// managing tokens yourself. Channels joined later through
+    /// [`subscribe()`](Self::subscribe) also receive `token`.
+    ///
+    /// Requires the `realtime` feature to be enabled.
+    #[cfg(feature = "realtime")]
+    pub async fn set_realtime_auth(&self, token: &str) -> Result<()> {
+        self.realtime_channels.set_token(token);
+        self.realtime_channels.push_token().await
+    }
+
     /// Get the Realtime WebSocket URL.
     ///
     /// Use this to initialize your own `supabase-realtime-rs` client if needed.
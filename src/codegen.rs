@@ -0,0 +1,367 @@
+//! Rust type generation from the PostgREST OpenAPI schema.
+//!
+//! PostgREST describes every exposed table, view and function in an OpenAPI
+//! (Swagger 2.0) document served at the root of the REST URL. [`generate`]
+//! turns that document into Rust source: one struct per table or view, one
+//! `...Args` struct per function, and one enum per Postgres enum type.
+//!
+//! The `supabase-gen-types` binary wraps this module.
+//!
+//! # Limitations
+//!
+//! The spec does not describe function return types, and marks columns as
+//! required only when they are `NOT NULL` without a default. Other columns
+//! are generated as `Option<T>`. Date, time and UUID columns become `String`.
+//!
+//! Generation fails when two items would get the same type name, such as a
+//! table and an enum both called `status`, or enums of the same name in
+//! different schemas. It also fails when two columns of a table (`userId`
+//! and `user_id`) or two values of an enum (`on-hold` and `on_hold`) would
+//! get the same field or variant name.
+
+use crate::client::SupabaseClient;
+use crate::error::{Error, Result};
+use crate::query::check_response;
+use reqwest::header::ACCEPT;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Options for [`generate`].
+#[derive(Debug, Clone, Default)]
+pub struct CodegenOptions {
+    /// Also derive `SupabaseTable` for tables and views (needs the `derive`
+    /// feature in the crate using the generated code)
+    pub derive_table: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Spec {
+    #[serde(default)]
+    definitions: BTreeMap<String, Schema>,
+    #[serde(default)]
+    paths: BTreeMap<String, BTreeMap<String, Operation>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Schema {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    properties: BTreeMap<String, Property>,
+    #[serde(default)]
+    required: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Property {
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    items: Option<Box<Property>>,
+    #[serde(default, rename = "enum")]
+    variants: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Operation {
+    #[serde(default)]
+    parameters: Vec<Parameter>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Parameter {
+    #[serde(default, rename = "in")]
+    location: Option<String>,
+    #[serde(default)]
+    schema: Option<Schema>,
+}
+
+impl SupabaseClient {
+    /// Fetch the OpenAPI spec PostgREST serves for the configured schema.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use supabase_client_rs::codegen::{CodegenOptions, generate};
+    ///
+    /// let spec = client.openapi_spec().await?;
+    /// let source = generate(&spec, &CodegenOptions::default())?;
+    /// std::fs::write("src/db_types.rs", source)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn openapi_spec(&self) -> Result<String> {
+        let response = self
+            .http()
+            .get(format!("{}/", self.config().rest_url()))
+            .header(ACCEPT, "application/openapi+json")
+            .header("Accept-Profile", &self.config().schema)
+            .send()
+            .await?;
+        Ok(check_response(response).await?.text().await?)
+    }
+}
+
+/// Generate Rust types from a PostgREST OpenAPI spec.
+///
+/// # Example
+///
+/// ```rust
+/// use supabase_client_rs::codegen::{CodegenOptions, generate};
+///
+/// let spec = r#"{
+///     "definitions": {
+///         "users": {
+///             "required": ["id"],
+///             "properties": {
+///                 "id": {"type": "integer", "format": "bigint"},
+///                 "email": {"type": "string", "format": "text"}
+///             }
+///         }
+///     }
+/// }"#;
+///
+/// let source = generate(spec, &CodegenOptions::default()).unwrap();
+/// assert!(source.contains("pub struct Users {"));
+/// assert!(source.contains("pub email: Option<String>,"));
+/// ```
+pub fn generate(spec: &str, options: &CodegenOptions) -> Result<String> {
+    let spec: Spec = serde_json::from_str(spec)?;
+    let mut enums = BTreeMap::new();
+    let mut items = String::new();
+    let mut types = BTreeMap::new();
+
+    for (name, schema) in &spec.definitions {
+        let doc = schema
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("A row of the `{}` table or view.", name));
+        claim(
+            &mut types,
+            "type",
+            type_name(name),
+            format!("table `{}`", name),
+        )?;
+        write_struct(&mut items, name, &doc, schema, &mut enums, options, true)?;
+    }
+
+    for (path, operations) in &spec.paths {
+        let Some(function) = path.strip_prefix("/rpc/") else {
+            continue;
+        };
+        let args = operations
+            .get("post")
+            .into_iter()
+            .flat_map(|op| &op.parameters)
+            .filter(|p| p.location.as_deref() == Some("body"))
+            .find_map(|p| p.schema.as_ref());
+        if let Some(args) = args.filter(|args| !args.properties.is_empty()) {
+            let doc = format!("Arguments of the `{}` function.", function);
+            let name = format!("{}_args", function);
+            let origin = format!("arguments of function `{}`", function);
+            claim(&mut types, "type", type_name(&name), origin)?;
+            write_struct(&mut items, &name, &doc, args, &mut enums, options, false)?;
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("//! Generated by supabase-gen-types from the PostgREST OpenAPI spec.\n\n");
+    out.push_str("use serde::{Deserialize, Serialize};\n");
+    if options.derive_table {
+        out.push_str("use supabase_client_rs::SupabaseTable;\n");
+    }
+    for (ty, (name, variants)) in &enums {
+        claim(&mut types, "type", ty.clone(), format!("enum `{}`", name))?;
+        write_enum(&mut out, ty, name, variants)?;
+    }
+    out.push_str(&items);
+    Ok(out)
+}
+
+/// Enum types by Rust type name, with their Postgres name and variants.
+type Enums = BTreeMap<String, (String, Vec<String>)>;
+
+/// Reserve the generated `kind` name `ident` (a type, field or variant)
+/// for `origin`.
+fn claim(
+    claimed: &mut BTreeMap<String, String>,
+    kind: &str,
+    ident: String,
+    origin: String,
+) -> Result<()> {
+    if let Some(existing) = claimed.get(&ident) {
+        return Err(Error::config(format!(
+            "{} and {} would both generate {} `{}`",
+            existing, origin, kind, ident
+        )));
+    }
+    claimed.insert(ident, origin);
+    Ok(())
+}
+
+fn write_struct(
+    out: &mut String,
+    name: &str,
+    doc: &str,
+    schema: &Schema,
+    enums: &mut Enums,
+    options: &CodegenOptions,
+    table: bool,
+) -> Result<()> {
+    let derive_table = table && options.derive_table;
+    out.push('\n');
+    for line in doc.lines() {
+        let _ = writeln!(out, "/// {}", line);
+    }
+    if derive_table {
+        out.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SupabaseTable)]\n");
+        let _ = writeln!(out, "#[supabase(table = {:?})]", name);
+    } else {
+        out.push_str("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\n");
+    }
+    let _ = writeln!(out, "pub struct {} {{", type_name(name));
+
+    let mut fields = BTreeMap::new();
+    for (column, property) in &schema.properties {
+        let mut ty = rust_type(property, enums)?;
+        if !schema.required.contains(column) {
+            ty = format!("Option<{}>", ty);
+        }
+        let field = field_name(column);
+        let origin = format!("column `{}` of `{}`", column, name);
+        claim(&mut fields, "field", field.clone(), origin)?;
+        if field.trim_start_matches("r#") != column {
+            let _ = writeln!(out, "    #[serde(rename = {:?})]", column);
+            if derive_table {
+                let _ = writeln!(out, "    #[supabase(rename = {:?})]", column);
+            }
+        }
+        let primary_key = property
+            .description
+            .as_deref()
+            .is_some_and(|d| d.contains("<pk/>"));
+        if derive_table && primary_key {
+            out.push_str("    #[supabase(primary_key)]\n");
+        }
+        let _ = writeln!(out, "    pub {}: {},", field, ty);
+    }
+    out.push_str("}\n");
+    Ok(())
+}
+
+fn write_enum(out: &mut String, ty: &str, name: &str, variants: &[String]) -> Result<()> {
+    out.push('\n');
+    let _ = writeln!(out, "/// The `{}` enum type.", name);
+    out.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\n");
+    let _ = writeln!(out, "pub enum {} {{", ty);
+    let mut idents = BTreeMap::new();
+    for variant in variants {
+        let ident = type_name(variant);
+        let origin = format!("value `{}` of enum `{}`", variant, name);
+        claim(&mut idents, "variant", ident.clone(), origin)?;
+        let _ = writeln!(out, "    #[serde(rename = {:?})]", variant);
+        let _ = writeln!(out, "    {},", ident);
+    }
+    out.push_str("}\n");
+    Ok(())
+}
+
+/// Map a property to a Rust type, registering enum types on the way.
+fn rust_type(property: &Property, enums: &mut Enums) -> Result<String> {
+    let format = property.format.as_deref().unwrap_or_default();
+    if let Some(variants) = &property.variants {
+        let short = format.rsplit('.').next().unwrap_or(format);
+        let name = if format.is_empty() { "enum" } else { format };
+        let ty = type_name(if short.is_empty() { "enum" } else { short });
+        match enums.get(&ty) {
+            Some((existing, _)) if existing != name => {
+                return Err(Error::config(format!(
+                    "enum `{}` and enum `{}` would both generate type `{}`",
+                    existing, name, ty
+                )));
+            }
+            Some(_) => {}
+            None => {
+                enums.insert(ty.clone(), (name.to_string(), variants.clone()));
+            }
+        }
+        return Ok(ty);
+    }
+    if property.kind.as_deref() == Some("array") {
+        let item = match &property.items {
+            Some(items) => rust_type(items, enums)?,
+            None => "serde_json::Value".to_string(),
+        };
+        return Ok(format!("Vec<{}>", item));
+    }
+
+    let ty = match (format, property.kind.as_deref()) {
+        ("smallint", _) => "i16",
+        ("integer", _) => "i32",
+        ("bigint", _) => "i64",
+        ("real", _) => "f32",
+        ("double precision" | "numeric", _) => "f64",
+        ("boolean", _) => "bool",
+        ("json" | "jsonb", _) => "serde_json::Value",
+        (_, Some("integer")) => "i64",
+        (_, Some("number")) => "f64",
+        (_, Some("boolean")) => "bool",
+        (_, Some("string")) => "String",
+        _ => "serde_json::Value",
+    };
+    Ok(ty.to_string())
+}
+
+/// Convert a table, function or enum name to a `PascalCase` type name.
+fn type_name(name: &str) -> String {
+    let mut out = String::new();
+    for word in name.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+    if !out.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        out.insert(0, 'T');
+    }
+    out
+}
+
+/// Convert a column name to a `snake_case` field name.
+fn field_name(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+        } else {
+            out.push('_');
+        }
+    }
+    if !out.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        out.insert(0, '_');
+    }
+    match out.as_str() {
+        "self" | "Self" | "super" | "crate" | "_" => format!("{}_", out),
+        "as" | "async" | "await" | "break" | "const" | "continue" | "dyn" | "else" | "enum"
+        | "extern" | "false" | "fn" | "for" | "gen" | "if" | "impl" | "in" | "let" | "loop"
+        | "match" | "mod" | "move" | "mut" | "pub" | "ref" | "return" | "static" | "struct"
+        | "trait" | "true" | "type" | "unsafe" | "use" | "where" | "while" | "yield" | "box"
+        | "do" | "try" | "abstract" | "become" | "final" | "macro" | "override" | "priv"
+        | "typeof" | "unsized" | "virtual" => format!("r#{}", out),
+        _ => out,
+    }
+}
@@ -1,5 +1,7 @@
 //! Configuration types for the Supabase client.
 
+use crate::traits::SessionStorage;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Configuration options for the Supabase client.
@@ -28,6 +30,9 @@ pub struct SupabaseConfig {
 
     /// Persist session
     pub persist_session: bool,
+
+    /// Where sessions are persisted (default: in memory)
+    pub session_storage: Option<Arc<dyn SessionStorage>>,
 }
 
 impl SupabaseConfig {
@@ -42,6 +47,7 @@ impl SupabaseConfig {
             headers: Vec::new(),
             auto_refresh_token: true,
             persist_session: true,
+            session_storage: None,
         }
     }
 
@@ -81,6 +87,12 @@ impl SupabaseConfig {
         self
     }
 
+    /// Set the storage used to persist sessions.
+    pub fn session_storage(mut self, storage: impl SessionStorage + 'static) -> Self {
+        self.session_storage = Some(Arc::new(storage));
+        self
+    }
+
     /// Get the REST API URL.
     pub fn rest_url(&self) -> String {
         format!("{}/rest/v1", self.url.trim_end_matches('/'))
@@ -1,5 +1,6 @@
 //! Error types for the Supabase client.
 
+use serde::Deserialize;
 use thiserror::Error;
 
 /// The main error type for Supabase operations.
@@ -17,6 +18,10 @@ pub enum Error {
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
+    /// I/O error (session files, local files)
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     /// JSON serialization/deserialization error
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
@@ -32,6 +37,8 @@ pub enum Error {
         details: Option<String>,
         /// Optional hint for fixing the error
         hint: Option<String>,
+        /// HTTP status of the response, if the error came from one
+        status: Option<u16>,
     },
 
     /// Authentication error
@@ -76,8 +83,232 @@ impl Error {
             code,
             details,
             hint,
+            status: None,
+        }
+    }
+
+    /// Create a PostgREST error from a non-2xx response.
+    ///
+    /// Decodes PostgREST's JSON error payload, falling back to the raw body
+    /// when it is not JSON.
+    pub fn postgrest_response(status: u16, body: &str) -> Self {
+        let (message, code, details, hint) = match serde_json::from_str::<PostgrestErrorBody>(body)
+        {
+            Ok(err) => (err.message, err.code, err.details, err.hint),
+            Err(_) => (
+                format!("request failed with status {}", status),
+                None,
+                (!body.is_empty()).then(|| body.to_string()),
+                None,
+            ),
+        };
+        Self::PostgREST {
+            message,
+            code,
+            details,
+            hint,
+            status: Some(status),
+        }
+    }
+
+    /// The typed PostgREST/Postgres error code, if this is a PostgREST error
+    /// with a code.
+    pub fn postgrest_code(&self) -> Option<PostgrestErrorCode> {
+        match self {
+            Self::PostgREST {
+                code: Some(code), ..
+            } => Some(PostgrestErrorCode::from(code.as_str())),
+            _ => None,
+        }
+    }
+
+    /// Whether a unique constraint rejected the write (`23505`).
+    pub fn is_unique_violation(&self) -> bool {
+        self.postgrest_code() == Some(PostgrestErrorCode::UniqueViolation)
+    }
+
+    /// Whether a foreign key constraint rejected the write (`23503`).
+    pub fn is_foreign_key_violation(&self) -> bool {
+        self.postgrest_code() == Some(PostgrestErrorCode::ForeignKeyViolation)
+    }
+
+    /// Whether row level security or missing grants denied access (`42501`).
+    pub fn is_rls_denied(&self) -> bool {
+        self.postgrest_code() == Some(PostgrestErrorCode::InsufficientPrivilege)
+    }
+
+    /// Whether the JWT was rejected by PostgREST (`PGRST301`, `PGRST303`).
+    pub fn is_jwt_error(&self) -> bool {
+        matches!(
+            self.postgrest_code(),
+            Some(PostgrestErrorCode::JwtInvalid | PostgrestErrorCode::JwtClaimsInvalid)
+        )
+    }
+}
+
+/// Error codes reported in `Error::PostgREST::code`.
+///
+/// Covers PostgREST's own `PGRST*` codes and common Postgres SQLSTATE codes.
+/// Codes without a dedicated variant are kept as [`Self::OtherPostgrest`] or
+/// [`Self::OtherSqlstate`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PostgrestErrorCode {
+    /// `PGRST000`: could not connect to the database
+    DbConnection,
+    /// `PGRST116`: a single row was requested but zero or several matched
+    SingularResult,
+    /// `PGRST124`: more rows would be affected than `max-affected` allows
+    MaxAffectedExceeded,
+    /// `PGRST200`: no relationship found between the embedded tables
+    RelationshipNotFound,
+    /// `PGRST202`: the function was not found in the schema cache
+    FunctionNotFound,
+    /// `PGRST204`: the column was not found in the schema cache
+    ColumnNotFound,
+    /// `PGRST205`: the table was not found in the schema cache
+    TableNotFound,
+    /// `PGRST301`: the JWT could not be decoded or verified
+    JwtInvalid,
+    /// `PGRST302`: anonymous access is disabled
+    AnonymousDisabled,
+    /// `PGRST303`: the JWT claims are invalid (e.g. expired)
+    JwtClaimsInvalid,
+    /// `22P02`: invalid input syntax for a type
+    InvalidTextRepresentation,
+    /// `23502`: NOT NULL constraint violated
+    NotNullViolation,
+    /// `23503`: foreign key constraint violated
+    ForeignKeyViolation,
+    /// `23505`: unique constraint violated
+    UniqueViolation,
+    /// `23514`: CHECK constraint violated
+    CheckViolation,
+    /// `23P01`: exclusion constraint violated
+    ExclusionViolation,
+    /// `40001`: serialization failure
+    SerializationFailure,
+    /// `40P01`: deadlock detected
+    DeadlockDetected,
+    /// `42501`: insufficient privilege, including row level security denials
+    InsufficientPrivilege,
+    /// `42P01`: the table does not exist
+    UndefinedTable,
+    /// `42703`: the column does not exist
+    UndefinedColumn,
+    /// `42883`: the function does not exist
+    UndefinedFunction,
+    /// `57014`: the statement was canceled, usually by `statement_timeout`
+    QueryCanceled,
+    /// `P0001`: raised by `RAISE EXCEPTION` in PL/pgSQL
+    RaiseException,
+    /// Any other `PGRST*` code
+    OtherPostgrest(String),
+    /// Any other SQLSTATE code
+    OtherSqlstate(String),
+}
+
+impl PostgrestErrorCode {
+    /// The code as sent by the server.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::DbConnection => "PGRST000",
+            Self::SingularResult => "PGRST116",
+            Self::MaxAffectedExceeded => "PGRST124",
+            Self::RelationshipNotFound => "PGRST200",
+            Self::FunctionNotFound => "PGRST202",
+            Self::ColumnNotFound => "PGRST204",
+            Self::TableNotFound => "PGRST205",
+            Self::JwtInvalid => "PGRST301",
+            Self::AnonymousDisabled => "PGRST302",
+            Self::JwtClaimsInvalid => "PGRST303",
+            Self::InvalidTextRepresentation => "22P02",
+            Self::NotNullViolation => "23502",
+            Self::ForeignKeyViolation => "23503",
+            Self::UniqueViolation => "23505",
+            Self::CheckViolation => "23514",
+            Self::ExclusionViolation => "23P01",
+            Self::SerializationFailure => "40001",
+            Self::DeadlockDetected => "40P01",
+            Self::InsufficientPrivilege => "42501",
+            Self::UndefinedTable => "42P01",
+            Self::UndefinedColumn => "42703",
+            Self::UndefinedFunction => "42883",
+            Self::QueryCanceled => "57014",
+            Self::RaiseException => "P0001",
+            Self::OtherPostgrest(code) | Self::OtherSqlstate(code) => code,
         }
     }
+
+    /// Whether this is a PostgREST (`PGRST*`) code rather than a SQLSTATE.
+    pub fn is_postgrest(&self) -> bool {
+        self.as_str().starts_with("PGRST")
+    }
+
+    /// The two-character SQLSTATE class (e.g. `"23"`), if this is a SQLSTATE.
+    pub fn sqlstate_class(&self) -> Option<&str> {
+        (!self.is_postgrest()).then(|| self.as_str().get(..2).unwrap_or_default())
+    }
+
+    /// Whether an integrity constraint was violated (SQLSTATE class `23`).
+    pub fn is_integrity_violation(&self) -> bool {
+        self.sqlstate_class() == Some("23")
+    }
+
+    /// Whether retrying the transaction may succeed (`40001`, `40P01`).
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::SerializationFailure | Self::DeadlockDetected)
+    }
+}
+
+impl From<&str> for PostgrestErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "PGRST000" => Self::DbConnection,
+            "PGRST116" => Self::SingularResult,
+            "PGRST124" => Self::MaxAffectedExceeded,
+            "PGRST200" => Self::RelationshipNotFound,
+            "PGRST202" => Self::FunctionNotFound,
+            "PGRST204" => Self::ColumnNotFound,
+            "PGRST205" => Self::TableNotFound,
+            "PGRST301" => Self::JwtInvalid,
+            "PGRST302" => Self::AnonymousDisabled,
+            "PGRST303" => Self::JwtClaimsInvalid,
+            "22P02" => Self::InvalidTextRepresentation,
+            "23502" => Self::NotNullViolation,
+            "23503" => Self::ForeignKeyViolation,
+            "23505" => Self::UniqueViolation,
+            "23514" => Self::CheckViolation,
+            "23P01" => Self::ExclusionViolation,
+            "40001" => Self::SerializationFailure,
+            "40P01" => Self::DeadlockDetected,
+            "42501" => Self::InsufficientPrivilege,
+            "42P01" => Self::UndefinedTable,
+            "42703" => Self::UndefinedColumn,
+            "42883" => Self::UndefinedFunction,
+            "57014" => Self::QueryCanceled,
+            "P0001" => Self::RaiseException,
+            other if other.starts_with("PGRST") => Self::OtherPostgrest(other.to_string()),
+            other => Self::OtherSqlstate(other.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for PostgrestErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error payload returned by PostgREST.
+#[derive(Debug, Deserialize)]
+struct PostgrestErrorBody {
+    message: String,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    details: Option<String>,
+    #[serde(default)]
+    hint: Option<String>,
 }
 
 /// Convert RealtimeError to our Error type when the realtime feature is enabled
@@ -0,0 +1,147 @@
+//! Query plan inspection.
+
+use crate::error::{Error, Result};
+use crate::query::QueryBuilder;
+use reqwest::header::{ACCEPT, HeaderValue};
+use serde_json::Value;
+
+/// Output format of a query plan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExplainFormat {
+    /// `EXPLAIN (FORMAT JSON)`, parsed into [`QueryPlan::Json`]
+    #[default]
+    Json,
+    /// `EXPLAIN (FORMAT TEXT)`, returned as [`QueryPlan::Text`]
+    Text,
+}
+
+/// Options for [`QueryBuilder::explain`].
+#[derive(Debug, Clone, Default)]
+pub struct ExplainOptions {
+    /// Run the query and report actual timings and row counts
+    pub analyze: bool,
+    /// Include output columns and other details
+    pub verbose: bool,
+    /// Include buffer usage (with `analyze`)
+    pub buffers: bool,
+    /// Output format
+    pub format: ExplainFormat,
+}
+
+/// A query plan returned by [`QueryBuilder::explain`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryPlan {
+    /// Plan in Postgres' JSON format
+    Json(Value),
+    /// Plan in Postgres' text format
+    Text(String),
+}
+
+impl QueryPlan {
+    /// Whether any node of the plan is a sequential scan.
+    pub fn has_seq_scan(&self) -> bool {
+        match self {
+            Self::Json(plan) => has_node_type(plan, "Seq Scan"),
+            Self::Text(plan) => plan.contains("Seq Scan"),
+        }
+    }
+}
+
+impl QueryBuilder {
+    /// Get the query plan instead of the rows.
+    ///
+    /// Sends `Accept: application/vnd.pgrst.plan`. The PostgREST server must
+    /// have `db-plan-enabled` turned on. With `analyze`, the query runs;
+    /// combine with [`dry_run`](Self::dry_run) to explain writes safely.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use supabase_client_rs::ExplainOptions;
+    ///
+    /// let plan = client
+    ///     .from("orders")
+    ///     .select("*")
+    ///     .eq("customer_id", "42")
+    ///     .explain(ExplainOptions {
+    ///         analyze: true,
+    ///         ..Default::default()
+    ///     })
+    ///     .await?;
+    /// assert!(!plan.has_seq_scan());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn explain(self, options: ExplainOptions) -> Result<QueryPlan> {
+        let accept = HeaderValue::from_str(&plan_media_type(&options))
+            .map_err(|e| Error::config(e.to_string()))?;
+        let body = self.header(ACCEPT, accept).execute().await?.text().await?;
+        Ok(match options.format {
+            ExplainFormat::Json => QueryPlan::Json(serde_json::from_str(&body)?),
+            ExplainFormat::Text => QueryPlan::Text(body),
+        })
+    }
+}
+
+/// Build the `application/vnd.pgrst.plan` media type for `options`.
+fn plan_media_type(options: &ExplainOptions) -> String {
+    let format = match options.format {
+        ExplainFormat::Json => "json",
+        ExplainFormat::Text => "text",
+    };
+    let flags: Vec<&str> = [
+        (options.analyze, "analyze"),
+        (options.verbose, "verbose"),
+        (options.buffers, "buffers"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, flag)| enabled.then_some(flag))
+    .collect();
+
+    let mut media_type = format!(
+        "application/vnd.pgrst.plan+{}; for=\"application/json\"",
+        format
+    );
+    if !flags.is_empty() {
+        media_type.push_str("; options=");
+        media_type.push_str(&flags.join("|"));
+    }
+    media_type
+}
+
+fn has_node_type(value: &Value, node_type: &str) -> bool {
+    match value {
+        Value::Object(map) => {
+            map.get("Node Type").and_then(Value::as_str) == Some(node_type)
+                || map.values().any(|v| has_node_type(v, node_type))
+        }
+        Value::Array(items) => items.iter().any(|v| has_node_type(v, node_type)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_media_type() {
+        assert_eq!(
+            plan_media_type(&ExplainOptions::default()),
+            r#"application/vnd.pgrst.plan+json; for="application/json""#
+        );
+        let options = ExplainOptions {
+            analyze: true,
+            buffers: true,
+            format: ExplainFormat::Text,
+            ..Default::default()
+        };
+        assert_eq!(
+            plan_media_type(&options),
+            r#"application/vnd.pgrst.plan+text; for="application/json"; options=analyze|buffers"#
+        );
+    }
+}
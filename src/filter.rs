@@ -0,0 +1,379 @@
+//! Typed filter expressions.
+//!
+//! [`Filter`] builds PostgREST filters as a tree instead of hand-written
+//! strings. Values are taken as any `Serialize` type and rendered with the
+//! quoting PostgREST expects, including inside `and`/`or` groups.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// A PostgREST filter expression.
+///
+/// Build conditions with the constructors, combine them with
+/// [`and`](Self::and), [`or`](Self::or), [`all`](Self::all) and
+/// [`any`](Self::any), negate them with `!`, and apply them with
+/// [`QueryBuilder::filter`](crate::QueryBuilder::filter).
+///
+/// Values that cannot be represented as JSON are treated as `null`.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use supabase_client_rs::SupabaseClient;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = SupabaseClient::new("url", "key")?;
+/// use supabase_client_rs::Filter;
+///
+/// let adults_or_admins = Filter::gte("age", 18)
+///     .or(Filter::eq("role", "admin"))
+///     .and(!Filter::is("deleted_at", None::<()>))
+///     .and(Filter::overlaps("tags", ["rust", "postgres"]));
+///
+/// let users = client
+///     .from("users")
+///     .select("*")
+///     .filter(adults_or_admins)
+///     .execute()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter(Node);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Condition {
+        column: String,
+        operator: Operator,
+        value: Value,
+    },
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operator {
+    Eq,
+    Neq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Like,
+    Ilike,
+    Is,
+    In,
+    Contains,
+    ContainedBy,
+    Overlaps,
+    StrictlyLeft,
+    StrictlyRight,
+    NotExtendRight,
+    NotExtendLeft,
+    Adjacent,
+    TextSearch(TextSearch, Option<String>),
+}
+
+/// How the query of a full-text search filter is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextSearch {
+    /// `to_tsquery`: the query uses tsquery syntax (`fat & cat`)
+    Query,
+    /// `plainto_tsquery`: all words must match
+    Plain,
+    /// `phraseto_tsquery`: the words must match as a phrase
+    Phrase,
+    /// `websearch_to_tsquery`: search-engine syntax (`"fat cat" -dog`)
+    Websearch,
+}
+
+impl TextSearch {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Query => "fts",
+            Self::Plain => "plfts",
+            Self::Phrase => "phfts",
+            Self::Websearch => "wfts",
+        }
+    }
+}
+
+impl Operator {
+    fn as_str(&self) -> String {
+        let op = match self {
+            Self::Eq => "eq",
+            Self::Neq => "neq",
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+            Self::Like => "like",
+            Self::Ilike => "ilike",
+            Self::Is => "is",
+            Self::In => "in",
+            Self::Contains => "cs",
+            Self::ContainedBy => "cd",
+            Self::Overlaps => "ov",
+            Self::StrictlyLeft => "sl",
+            Self::StrictlyRight => "sr",
+            Self::NotExtendRight => "nxr",
+            Self::NotExtendLeft => "nxl",
+            Self::Adjacent => "adj",
+            Self::TextSearch(kind, None) => kind.as_str(),
+            Self::TextSearch(kind, Some(config)) => {
+                return format!("{}({})", kind.as_str(), config);
+            }
+        };
+        op.to_string()
+    }
+}
+
+/// Define constructors for simple `column.op.value` conditions.
+macro_rules! conditions {
+    ($($(#[$doc:meta])* $name:ident => $op:ident;)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(column: impl Into<String>, value: impl Serialize) -> Self {
+                Self::condition(column, Operator::$op, value)
+            }
+        )*
+    };
+}
+
+impl Filter {
+    fn condition(column: impl Into<String>, operator: Operator, value: impl Serialize) -> Self {
+        Self(Node::Condition {
+            column: column.into(),
+            operator,
+            value: serde_json::to_value(value).unwrap_or(Value::Null),
+        })
+    }
+
+    conditions! {
+        /// `column = value`
+        eq => Eq;
+        /// `column <> value`
+        neq => Neq;
+        /// `column > value`
+        gt => Gt;
+        /// `column >= value`
+        gte => Gte;
+        /// `column < value`
+        lt => Lt;
+        /// `column <= value`
+        lte => Lte;
+        /// `column LIKE pattern` (use `*` or `%` as wildcard)
+        like => Like;
+        /// `column ILIKE pattern` (use `*` or `%` as wildcard)
+        ilike => Ilike;
+        /// `column IS value`, for `null` (`None`), `true` and `false`
+        is => Is;
+        /// `column IN (values)`, for a list of values
+        in_ => In;
+        /// `column @> value`: the array, range or jsonb contains `value`
+        contains => Contains;
+        /// `column <@ value`: the array, range or jsonb is contained in `value`
+        contained_by => ContainedBy;
+        /// `column && value`: the array or range shares an element with `value`
+        overlaps => Overlaps;
+        /// `column << range`: strictly left of a range literal or `(lower, upper)` pair
+        strictly_left => StrictlyLeft;
+        /// `column >> range`: strictly right of a range literal or `(lower, upper)` pair
+        strictly_right => StrictlyRight;
+        /// `column &< range`: does not extend to the right of `range`
+        not_extend_right => NotExtendRight;
+        /// `column &> range`: does not extend to the left of `range`
+        not_extend_left => NotExtendLeft;
+        /// `column -|- range`: adjacent to `range`
+        adjacent => Adjacent;
+    }
+
+    /// Full-text search on a tsvector `column`.
+    ///
+    /// `config` selects the text search configuration (e.g. `"english"`).
+    pub fn text_search(
+        column: impl Into<String>,
+        query: impl Into<String>,
+        kind: TextSearch,
+        config: Option<&str>,
+    ) -> Self {
+        let operator = Operator::TextSearch(kind, config.map(String::from));
+        Self::condition(column, operator, query.into())
+    }
+
+    /// Match when every filter matches.
+    pub fn all(filters: impl IntoIterator<Item = Filter>) -> Self {
+        Self(Node::And(filters.into_iter().collect()))
+    }
+
+    /// Match when at least one filter matches.
+    pub fn any(filters: impl IntoIterator<Item = Filter>) -> Self {
+        Self(Node::Or(filters.into_iter().collect()))
+    }
+
+    /// Match when both this filter and `other` match.
+    pub fn and(self, other: Filter) -> Self {
+        match self.0 {
+            Node::And(mut filters) => {
+                filters.push(other);
+                Self(Node::And(filters))
+            }
+            _ => Self::all([self, other]),
+        }
+    }
+
+    /// Match when this filter or `other` matches.
+    pub fn or(self, other: Filter) -> Self {
+        match self.0 {
+            Node::Or(mut filters) => {
+                filters.push(other);
+                Self(Node::Or(filters))
+            }
+            _ => Self::any([self, other]),
+        }
+    }
+
+    /// Render as a query parameter name and value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use supabase_client_rs::Filter;
+    ///
+    /// let filter = Filter::eq("status", "active").or(Filter::gt("age", 30));
+    /// assert_eq!(
+    ///     filter.to_param(),
+    ///     ("or".to_string(), "(status.eq.active,age.gt.30)".to_string())
+    /// );
+    /// ```
+    pub fn to_param(&self) -> (String, String) {
+        match &self.0 {
+            Node::Condition {
+                column,
+                operator,
+                value,
+            } => (column.clone(), predicate(operator, value, false)),
+            Node::And(filters) => ("and".to_string(), group(filters)),
+            Node::Or(filters) => ("or".to_string(), group(filters)),
+            Node::Not(inner) => {
+                let (name, value) = inner.to_param();
+                match &inner.0 {
+                    Node::Condition { .. } => (name, format!("not.{}", value)),
+                    _ => (format!("not.{}", name), value),
+                }
+            }
+        }
+    }
+
+    /// Render as an item of an `and`/`or` group.
+    fn to_nested(&self) -> String {
+        match &self.0 {
+            Node::Condition {
+                column,
+                operator,
+                value,
+            } => format!("{}.{}", column, predicate(operator, value, true)),
+            Node::And(filters) => format!("and{}", group(filters)),
+            Node::Or(filters) => format!("or{}", group(filters)),
+            Node::Not(inner) => match &inner.0 {
+                Node::Condition {
+                    column,
+                    operator,
+                    value,
+                } => format!("{}.not.{}", column, predicate(operator, value, true)),
+                _ => format!("not.{}", inner.to_nested()),
+            },
+        }
+    }
+}
+
+impl std::ops::Not for Filter {
+    type Output = Filter;
+
+    fn not(self) -> Filter {
+        match self.0 {
+            Node::Not(inner) => *inner,
+            _ => Filter(Node::Not(Box::new(self))),
+        }
+    }
+}
+
+/// Render the items of an `and`/`or` group.
+fn group(filters: &[Filter]) -> String {
+    let items: Vec<String> = filters.iter().map(Filter::to_nested).collect();
+    format!("({})", items.join(","))
+}
+
+/// Render `op.value` for a condition.
+fn predicate(operator: &Operator, value: &Value, nested: bool) -> String {
+    format!("{}.{}", operator.as_str(), render(operator, value, nested))
+}
+
+/// Render a value for `operator`, quoting it if it is `nested` in a group.
+fn render(operator: &Operator, value: &Value, nested: bool) -> String {
+    match (operator, value) {
+        (Operator::In, Value::Array(items)) => list(items, '(', ')', quote),
+        (Operator::In, value) => format!("({})", quote(&scalar(value))),
+        (Operator::Contains | Operator::ContainedBy | Operator::Overlaps, Value::Array(items)) => {
+            list(items, '{', '}', quote_array_item)
+        }
+        (
+            Operator::StrictlyLeft
+            | Operator::StrictlyRight
+            | Operator::NotExtendRight
+            | Operator::NotExtendLeft
+            | Operator::Adjacent,
+            Value::Array(bounds),
+        ) if bounds.len() == 2 => list(bounds, '(', ')', str::to_string),
+        (_, Value::String(s)) if nested => quote(s),
+        // jsonb objects contain commas, which would split the group
+        (_, Value::Object(_)) if nested => escape(&value.to_string()),
+        (_, value) => scalar(value),
+    }
+}
+
+/// Render a function argument for the query string of a `GET` RPC call.
+pub(crate) fn argument(value: &Value) -> String {
+    match value {
+        Value::Array(items) => list(items, '{', '}', quote_array_item),
+        value => scalar(value),
+    }
+}
+
+/// Render a scalar value without quoting.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Render `items` between `open` and `close`, quoting each with `quote`.
+fn list(items: &[Value], open: char, close: char, quote: fn(&str) -> String) -> String {
+    let items: Vec<String> = items.iter().map(|item| quote(&scalar(item))).collect();
+    format!("{}{}{}", open, items.join(","), close)
+}
+
+/// Quote a value containing PostgREST's reserved characters.
+fn quote(s: &str) -> String {
+    if s.contains([',', '.', ':', '(', ')', '"', '\\']) {
+        escape(s)
+    } else {
+        s.to_string()
+    }
+}
+
+/// Quote an element of a Postgres array literal if needed.
+fn quote_array_item(s: &str) -> String {
+    if s.is_empty() || s.contains([',', '{', '}', '"', '\\', ' ']) {
+        escape(s)
+    } else {
+        s.to_string()
+    }
+}
+
+fn escape(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
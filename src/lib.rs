@@ -7,7 +7,9 @@
 //!
 //! - **Database**: Uses [`postgrest-rs`](https://crates.io/crates/postgrest) for PostgREST queries
 //! - **Realtime**: Integrates with [`supabase-realtime-rs`](https://github.com/scaraude/supabase-realtime-rs)
-//! - **Auth, Storage, Functions**: Extensible via traits for community implementations
+//! - **Auth**: Built-in GoTrue client (requires the `auth` feature)
+//! - **Storage**: Built-in Storage client (requires the `storage` feature)
+//! - **Functions**: Extensible via traits for community implementations
 //!
 //! ## Quick Start
 //!
@@ -87,6 +89,32 @@
 //! # }
 //! ```
 //!
+//! ## Typed Results
+//!
+//! Query builders can deserialize responses directly into your own types.
+//! Error responses are returned as [`Error::PostgREST`]:
+//!
+//! ```rust,no_run
+//! # use supabase_client_rs::SupabaseClient;
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = SupabaseClient::new("url", "key")?;
+//! #[derive(serde::Deserialize)]
+//! struct User {
+//!     id: i64,
+//!     name: String,
+//! }
+//!
+//! let users: Vec<User> = client.from("users").select("*").execute_as().await?;
+//! let user: User = client.from("users").select("*").eq("id", "1").single().await?;
+//! let maybe: Option<User> = client.from("users").select("*").eq("id", "2").maybe_single().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! With the `derive` feature, `#[derive(SupabaseTable)]` generates table and
+//! column names for a struct, so queries can start from
+//! [`SupabaseClient::table`] instead of strings.
+//!
 //! ## Configuration
 //!
 //! For advanced configuration, use `SupabaseConfig`:
@@ -136,19 +164,52 @@
 //! - `rustls` (default): Use rustls for TLS
 //! - `native-tls`: Use native TLS instead of rustls
 //! - `realtime`: Enable Supabase Realtime support (requires `supabase-realtime-rs`)
+//! - `auth`: Enable the built-in Supabase Auth (GoTrue) client
+//! - `storage`: Enable the built-in Supabase Storage client
+//! - `derive`: Enable `#[derive(SupabaseTable)]`
+//! - `codegen`: Enable Rust type generation from the OpenAPI schema and the
+//!   `supabase-gen-types` binary
 
 #![warn(missing_docs)]
 #![warn(rustdoc::missing_crate_level_docs)]
 
+// Lets derive output refer to `::supabase_client_rs` inside this crate.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as supabase_client_rs;
+
+#[cfg(feature = "auth")]
+pub mod auth;
+mod bulk;
 mod client;
+#[cfg(feature = "codegen")]
+pub mod codegen;
 mod config;
 mod error;
+mod explain;
+mod filter;
+mod query;
+mod rpc;
+mod session_storage;
+#[cfg(feature = "storage")]
+pub mod storage;
+mod stream;
 pub mod traits;
 
 // Re-export main types
+pub use bulk::{BulkReport, BulkWrite, ChunkResult};
 pub use client::SupabaseClient;
 pub use config::SupabaseConfig;
-pub use error::{Error, Result};
+pub use error::{Error, PostgrestErrorCode, Result};
+pub use explain::{ExplainFormat, ExplainOptions, QueryPlan};
+pub use filter::{Filter, TextSearch};
+pub use query::{CountMode, Page, QueryBuilder};
+pub use rpc::RpcBuilder;
+pub use session_storage::{FileStorage, MemoryStorage};
+pub use stream::ByteStream;
+pub use traits::SupabaseTable;
+
+#[cfg(feature = "derive")]
+pub use supabase_client_rs_derive::SupabaseTable;
 
 // Re-export postgrest for advanced usage
 pub use postgrest;
@@ -241,4 +302,704 @@ mod tests {
         assert_eq!(config.timeout, std::time::Duration::from_secs(60));
         assert!(!config.auto_refresh_token);
     }
+
+    #[cfg(feature = "auth")]
+    #[test]
+    fn test_auth_client_starts_signed_out() {
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        assert!(client.auth().session().is_none());
+    }
+
+    #[cfg(feature = "auth")]
+    #[test]
+    fn test_auth_session_deserialize() {
+        let session: auth::Session = serde_json::from_value(serde_json::json!({
+            "access_token": "jwt",
+            "token_type": "bearer",
+            "expires_in": 3600,
+            "refresh_token": "refresh",
+            "user": { "id": "user-id", "aud": "authenticated", "email": "a@b.c" }
+        }))
+        .unwrap();
+        assert_eq!(session.user.email.as_deref(), Some("a@b.c"));
+        assert!(session.expires_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_session_storage_roundtrip() {
+        use traits::SessionStorage;
+
+        let dir = std::env::temp_dir().join(format!("supabase-rs-test-{}", std::process::id()));
+        let storage = FileStorage::new(&dir);
+
+        assert!(storage.get("key").await.unwrap().is_none());
+        storage.set("key", "value").await.unwrap();
+        assert_eq!(storage.get("key").await.unwrap().as_deref(), Some("value"));
+        storage.remove("key").await.unwrap();
+        assert!(storage.get("key").await.unwrap().is_none());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_file_session_storage_replaces_atomically() {
+        use traits::SessionStorage;
+
+        let dir = std::env::temp_dir().join(format!("supabase-rs-atomic-{}", std::process::id()));
+        let storage = FileStorage::new(&dir);
+
+        storage
+            .set("key", "a much longer first value")
+            .await
+            .unwrap();
+        storage.set("key", "second").await.unwrap();
+        assert_eq!(storage.get("key").await.unwrap().as_deref(), Some("second"));
+
+        // Only the session file is left, no temporary files
+        let files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(files, ["key.json"]);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.join("key.json"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn test_auth_state_change_on_sign_out() {
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let mut events = client.auth().on_auth_state_change();
+
+        client.auth().set_session(None).await;
+
+        let change = events.recv().await.unwrap();
+        assert_eq!(change.event, auth::AuthChangeEvent::SignedOut);
+        assert!(change.session.is_none());
+    }
+
+    #[cfg(feature = "auth")]
+    #[tokio::test]
+    async fn test_auth_session_swaps_authorization_header() {
+        let config = SupabaseConfig::new("https://example.supabase.co", "test-key")
+            .auto_refresh_token(false);
+        let client = SupabaseClient::with_config(config).unwrap();
+        let session: auth::Session = serde_json::from_value(serde_json::json!({
+            "access_token": "user-jwt",
+            "token_type": "bearer",
+            "expires_in": 3600,
+            "refresh_token": "refresh",
+            "user": { "id": "user-id" }
+        }))
+        .unwrap();
+
+        client.auth().set_session(Some(session)).await;
+        let request = client.from("users").select("*").build().unwrap();
+        assert_eq!(request.headers()["Authorization"], "Bearer user-jwt");
+        assert_eq!(request.headers()["apikey"], "test-key");
+
+        client.auth().set_session(None).await;
+        let request = client.from("users").select("*").build().unwrap();
+        assert_eq!(request.headers()["Authorization"], "Bearer test-key");
+    }
+
+    /// Start a Realtime server that reports every message it receives.
+    #[cfg(all(feature = "realtime", feature = "auth"))]
+    async fn realtime_server() -> (
+        std::net::SocketAddr,
+        tokio::sync::mpsc::UnboundedReceiver<serde_json::Value>,
+    ) {
+        use futures::StreamExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, received) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(message)) = socket.next().await {
+                if let Message::Text(text) = message {
+                    let message: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    let _ = tx.send(message);
+                }
+            }
+        });
+        (addr, received)
+    }
+
+    #[cfg(all(feature = "realtime", feature = "auth"))]
+    fn user_session(access_token: &str) -> auth::Session {
+        serde_json::from_value(serde_json::json!({
+            "access_token": access_token,
+            "token_type": "bearer",
+            "expires_in": 3600,
+            "refresh_token": "refresh",
+            "user": { "id": "user-id" }
+        }))
+        .unwrap()
+    }
+
+    #[cfg(all(feature = "realtime", feature = "auth"))]
+    #[tokio::test]
+    async fn test_channel_created_after_sign_in_uses_user_token() {
+        let (addr, mut received) = realtime_server().await;
+        let config =
+            SupabaseConfig::new(format!("http://{}", addr), "anon-key").auto_refresh_token(false);
+        let client = SupabaseClient::with_config(config).unwrap();
+        client
+            .auth()
+            .set_session(Some(user_session("user-jwt")))
+            .await;
+
+        client.realtime().connect().await.unwrap();
+        let channel = client.channel("db-changes", Default::default()).await;
+        client.subscribe(&channel).await.unwrap();
+
+        // The join carries the token the client was created with, the
+        // session's token follows right after
+        let mut events = Vec::new();
+        while events
+            .last()
+            .is_none_or(|m: &serde_json::Value| m["event"] != "access_token")
+        {
+            let wait = tokio::time::timeout(std::time::Duration::from_secs(5), received.recv());
+            events.push(wait.await.expect("no access_token message").unwrap());
+        }
+        let join = events.iter().find(|m| m["event"] == "phx_join").unwrap();
+        assert_eq!(join["topic"], "realtime:db-changes");
+        let token = events.last().unwrap();
+        assert_eq!(token["topic"], "realtime:db-changes");
+        assert_eq!(token["payload"]["access_token"], "user-jwt");
+    }
+
+    #[cfg(all(feature = "realtime", feature = "auth"))]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_back_to_back_token_changes_keep_latest_token() {
+        let (addr, mut received) = realtime_server().await;
+        let config =
+            SupabaseConfig::new(format!("http://{}", addr), "anon-key").auto_refresh_token(false);
+        let client = SupabaseClient::with_config(config).unwrap();
+        client.realtime().connect().await.unwrap();
+        let joined = client.channel("joined", Default::default()).await;
+        client.subscribe(&joined).await.unwrap();
+
+        // Sign in, then refresh right away
+        client
+            .auth()
+            .set_session(Some(user_session("first-jwt")))
+            .await;
+        client
+            .auth()
+            .set_session(Some(user_session("second-jwt")))
+            .await;
+        let later = client.channel("later", Default::default()).await;
+        client.subscribe(&later).await.unwrap();
+
+        let mut tokens = Vec::new();
+        let wait = std::time::Duration::from_millis(500);
+        while let Ok(Some(message)) = tokio::time::timeout(wait, received.recv()).await {
+            if message["event"] == "access_token" {
+                tokens.push((
+                    message["topic"].clone(),
+                    message["payload"]["access_token"].clone(),
+                ));
+            }
+        }
+        let last = |topic: &str| {
+            tokens
+                .iter()
+                .rev()
+                .find(|(t, _)| t == topic)
+                .map(|(_, token)| token.clone())
+        };
+        assert_eq!(last("realtime:joined").unwrap(), "second-jwt");
+        assert_eq!(last("realtime:later").unwrap(), "second-jwt");
+    }
+
+    #[test]
+    fn test_schema_override() {
+        let config = SupabaseConfig::new("https://example.supabase.co", "key").schema("custom");
+        let client = SupabaseClient::with_config(config).unwrap();
+        assert_eq!(client.config().schema, "custom");
+
+        let other = client.schema("other");
+        assert_eq!(other.config().schema, "other");
+        assert_eq!(client.config().schema, "custom");
+
+        let request = other.from("events").select("*").build().unwrap();
+        assert_eq!(request.headers()["Accept-Profile"], "other");
+    }
+
+    #[test]
+    fn test_query_builder_request() {
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let request = client
+            .from("users")
+            .select("id,name")
+            .eq("status", "active")
+            .build()
+            .unwrap();
+
+        assert_eq!(request.method(), reqwest::Method::GET);
+        assert_eq!(
+            request.url().as_str(),
+            "https://example.supabase.co/rest/v1/users?select=id%2Cname&status=eq.active"
+        );
+        assert_eq!(request.headers()["apikey"], "test-key");
+    }
+
+    #[test]
+    fn test_postgrest_error_from_response() {
+        let body = r#"{"code":"23505","details":"Key (email)=(a@b.c) already exists.","hint":null,"message":"duplicate key value violates unique constraint \"users_email_key\""}"#;
+        match Error::postgrest_response(409, body) {
+            Error::PostgREST {
+                code,
+                details,
+                hint,
+                status,
+                ..
+            } => {
+                assert_eq!(code.as_deref(), Some("23505"));
+                assert!(details.unwrap().contains("already exists"));
+                assert!(hint.is_none());
+                assert_eq!(status, Some(409));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        match Error::postgrest_response(502, "Bad Gateway") {
+            Error::PostgREST {
+                message, details, ..
+            } => {
+                assert_eq!(message, "request failed with status 502");
+                assert_eq!(details.as_deref(), Some("Bad Gateway"));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_postgrest_error_codes() {
+        let err = Error::postgrest_response(409, r#"{"code":"23505","message":"duplicate"}"#);
+        assert_eq!(
+            err.postgrest_code(),
+            Some(PostgrestErrorCode::UniqueViolation)
+        );
+        assert!(err.is_unique_violation());
+        assert!(!err.is_rls_denied());
+
+        let err = Error::postgrest_response(403, r#"{"code":"42501","message":"denied"}"#);
+        assert!(err.is_rls_denied());
+
+        let code = PostgrestErrorCode::from("23514");
+        assert!(code.is_integrity_violation());
+        assert_eq!(code.sqlstate_class(), Some("23"));
+
+        let code = PostgrestErrorCode::from("PGRST999");
+        assert_eq!(
+            code,
+            PostgrestErrorCode::OtherPostgrest("PGRST999".to_string())
+        );
+        assert!(code.is_postgrest());
+        assert_eq!(code.sqlstate_class(), None);
+    }
+
+    #[test]
+    fn test_upsert_json_ignore_duplicates() {
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let rows = vec![serde_json::json!({ "email": "a@b.c" })];
+        let request = client
+            .from("users")
+            .upsert_json(&rows)
+            .unwrap()
+            .on_conflict("email")
+            .ignore_duplicates()
+            .build()
+            .unwrap();
+
+        assert_eq!(request.method(), reqwest::Method::POST);
+        assert_eq!(
+            request.headers()["Prefer"],
+            "return=representation,resolution=ignore-duplicates"
+        );
+        assert!(request.url().query().unwrap().contains("on_conflict=email"));
+        assert_eq!(
+            request.body().and_then(|b| b.as_bytes()),
+            Some(br#"[{"email":"a@b.c"}]"#.as_slice())
+        );
+    }
+
+    #[test]
+    fn test_count_keeps_range() {
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let request = client
+            .from("users")
+            .select("*")
+            .paginate(50, 25)
+            .count(CountMode::Planned)
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()["Range"], "50-74");
+        assert_eq!(request.headers()["Prefer"], "count=planned");
+    }
+
+    #[test]
+    fn test_dry_run_with_max_affected() {
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let request = client
+            .from("users")
+            .update(r#"{"status": "inactive"}"#)
+            .eq("status", "pending")
+            .max_affected(100)
+            .dry_run()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.headers()["Prefer"],
+            "handling=strict,max-affected=100,tx=rollback,return=representation"
+        );
+    }
+
+    #[test]
+    fn test_filter_params() {
+        let filter = Filter::eq("name", "Smith, J.")
+            .or(!Filter::in_("role", ["admin", "a,b"]))
+            .or(Filter::all([
+                Filter::overlaps("tags", ["rust", "web dev"]),
+                Filter::strictly_left("during", (1, 10)),
+            ]));
+        assert_eq!(
+            filter.to_param(),
+            (
+                "or".to_string(),
+                r#"(name.eq."Smith, J.",role.not.in.(admin,"a,b"),and(tags.ov.{rust,"web dev"},during.sl.(1,10)))"#
+                    .to_string()
+            )
+        );
+
+        let jsonb = Filter::contains("meta", serde_json::json!({ "a": 1, "b": 2 })).or(
+            Filter::contained_by("meta", serde_json::json!({ "c": "x" })),
+        );
+        assert_eq!(
+            jsonb.to_param().1,
+            r#"(meta.cs."{\"a\":1,\"b\":2}",meta.cd."{\"c\":\"x\"}")"#
+        );
+        assert_eq!(
+            Filter::contains("meta", serde_json::json!({ "a": 1, "b": 2 })).to_param(),
+            ("meta".to_string(), r#"cs.{"a":1,"b":2}"#.to_string())
+        );
+
+        let negated = !Filter::is("deleted_at", None::<()>);
+        assert_eq!(
+            negated.to_param(),
+            ("deleted_at".to_string(), "not.is.null".to_string())
+        );
+        assert_eq!(!negated, Filter::is("deleted_at", None::<()>));
+
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let request = client
+            .from("posts")
+            .select("*")
+            .filter(Filter::text_search(
+                "body",
+                "fat cats",
+                TextSearch::Plain,
+                Some("english"),
+            ))
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("select=*&body=plfts%28english%29.fat+cats")
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_table() {
+        #[derive(SupabaseTable)]
+        #[allow(dead_code)]
+        struct UserProfile {
+            #[supabase(primary_key)]
+            user_id: i64,
+            #[supabase(rename = "mail")]
+            email: String,
+            #[supabase(skip)]
+            cached: bool,
+        }
+
+        assert_eq!(UserProfile::TABLE, "user_profile");
+        assert_eq!(UserProfile::COLUMNS, ["user_id", "mail"]);
+        assert_eq!(UserProfile::PRIMARY_KEY, ["user_id"]);
+        assert_eq!(UserProfile::EMAIL, "mail");
+
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let request = client
+            .table::<UserProfile>()
+            .select_all()
+            .eq(UserProfile::EMAIL, "a@b.co")
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "https://example.supabase.co/rest/v1/user_profile?select=user_id%2Cmail&mail=eq.a%40b.co"
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_table_follows_serde_renames() {
+        #[derive(serde::Deserialize, SupabaseTable)]
+        #[serde(rename_all = "camelCase", expecting = "an order row")]
+        #[allow(dead_code)]
+        struct Order {
+            id: i64,
+            #[serde(default)]
+            created_at: String,
+            #[serde(rename = "Total_Cents")]
+            total: i64,
+            #[serde(rename = "note")]
+            #[supabase(rename = "notes")]
+            note: Option<String>,
+            #[serde(skip)]
+            selected: bool,
+            #[serde(default, skip_deserializing)]
+            draft: Option<String>,
+            #[serde(skip_serializing)]
+            status: String,
+            #[serde(flatten)]
+            #[supabase(skip)]
+            extra: std::collections::HashMap<String, serde_json::Value>,
+        }
+
+        assert_eq!(
+            Order::COLUMNS,
+            ["id", "createdAt", "Total_Cents", "notes", "status"]
+        );
+        assert_eq!(Order::CREATED_AT, "createdAt");
+        assert_eq!(Order::PRIMARY_KEY, ["id"]);
+    }
+
+    #[cfg(feature = "codegen")]
+    #[test]
+    fn test_codegen_types() {
+        use codegen::{CodegenOptions, generate};
+
+        let spec = r##"{
+            "definitions": {"profiles": {"required": ["id", "status"], "properties": {
+                "id": {"type": "string", "format": "uuid", "description": "<pk/>"},
+                "status": {"type": "string", "format": "public.user_status", "enum": ["active", "on-hold"]},
+                "tags": {"type": "array", "format": "text[]", "items": {"type": "string"}},
+                "displayName": {"type": "string", "format": "text"}
+            }}},
+            "paths": {"/rpc/search": {"post": {"parameters": [
+                {"in": "body", "name": "args", "schema": {"required": ["query"], "properties": {
+                    "query": {"type": "string", "format": "text"}
+                }}},
+                {"$ref": "#/parameters/preferParams"}
+            ]}}}
+        }"##;
+        let options = CodegenOptions { derive_table: true };
+        let source = generate(spec, &options).unwrap();
+
+        assert!(source.contains("pub enum UserStatus {"));
+        assert!(source.contains("    #[serde(rename = \"on-hold\")]\n    OnHold,"));
+        assert!(source.contains("#[supabase(table = \"profiles\")]\npub struct Profiles {"));
+        assert!(source.contains("    #[supabase(primary_key)]\n    pub id: String,"));
+        assert!(source.contains("    #[serde(rename = \"displayName\")]"));
+        assert!(source.contains("    pub display_name: Option<String>,"));
+        assert!(source.contains("    pub status: UserStatus,"));
+        assert!(source.contains("    pub tags: Option<Vec<String>>,"));
+        assert!(source.contains("pub struct SearchArgs {\n    pub query: String,\n}"));
+
+        // Items that would share a type name are rejected
+        let table_and_enum = r#"{"definitions": {
+            "status": {"properties": {"id": {"type": "integer"}}},
+            "tasks": {"properties": {"state": {"format": "public.status", "enum": ["open"]}}}
+        }}"#;
+        let error = generate(table_and_enum, &options).unwrap_err().to_string();
+        assert!(
+            error.contains("would both generate type `Status`"),
+            "{}",
+            error
+        );
+
+        let two_schemas = r#"{"definitions": {"tasks": {"properties": {
+            "a": {"format": "public.state", "enum": ["open"]},
+            "b": {"format": "billing.state", "enum": ["paid"]}
+        }}}}"#;
+        let error = generate(two_schemas, &options).unwrap_err().to_string();
+        assert!(error.contains("`billing.state`"), "{}", error);
+
+        // Columns or enum values that would share a Rust name are rejected
+        let columns = r#"{"definitions": {"users": {"properties": {
+            "userId": {"type": "integer"},
+            "user_id": {"type": "integer"}
+        }}}}"#;
+        let error = generate(columns, &options).unwrap_err().to_string();
+        assert!(
+            error.contains("would both generate field `user_id`"),
+            "{}",
+            error
+        );
+
+        let values = r#"{"definitions": {"tasks": {"properties": {
+            "state": {"format": "public.state", "enum": ["on-hold", "on_hold"]}
+        }}}}"#;
+        let error = generate(values, &options).unwrap_err().to_string();
+        assert!(
+            error.contains("would both generate variant `OnHold`"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_rpc_typed_get_and_post() {
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let args = serde_json::json!({"query": "rust", "tags": ["a", "b c"]});
+
+        let request = client
+            .rpc_typed::<_, Vec<serde_json::Value>>("search", &args)
+            .unwrap()
+            .get()
+            .filter(Filter::gt("score", 0.5))
+            .into_query()
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), reqwest::Method::GET);
+        assert_eq!(
+            request.url().as_str(),
+            "https://example.supabase.co/rest/v1/rpc/search?score=gt.0.5&query=rust&tags=%7Ba%2C%22b+c%22%7D"
+        );
+        assert!(request.body().is_none_or(|b| b.as_bytes() == Some(b"")));
+
+        let request = client
+            .rpc_typed::<_, i64>("search", &args)
+            .unwrap()
+            .into_query()
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), reqwest::Method::POST);
+        assert_eq!(
+            request.body().and_then(|b| b.as_bytes()),
+            Some(br#"{"query":"rust","tags":["a","b c"]}"#.as_slice())
+        );
+
+        assert!(client.rpc_typed::<_, i64>("search", &[1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_query_plan_seq_scan() {
+        let plan = QueryPlan::Json(serde_json::json!([{"Plan": {
+            "Node Type": "Limit",
+            "Plans": [{"Node Type": "Seq Scan", "Relation Name": "orders"}]
+        }}]));
+        assert!(plan.has_seq_scan());
+        assert!(!QueryPlan::Text("Index Scan using orders_pkey".into()).has_seq_scan());
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_storage_public_url() {
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        assert_eq!(
+            client
+                .storage()
+                .get_public_url("avatars", "team/alice smith.png"),
+            "https://example.supabase.co/storage/v1/object/public/avatars/team/alice%20smith.png"
+        );
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_storage_transform_url() {
+        use storage::{ImageFormat, Resize, TransformOptions};
+
+        let client = create_client("https://example.supabase.co", "test-key").unwrap();
+        let options = TransformOptions {
+            width: Some(200),
+            height: Some(100),
+            resize: Some(Resize::Contain),
+            quality: Some(70),
+            format: Some(ImageFormat::Origin),
+        };
+        let url = client
+            .storage()
+            .get_public_url_with_transform("avatars", "alice.png", &options);
+        let url = url::Url::parse(&url).unwrap();
+        assert_eq!(
+            url.path(),
+            "/storage/v1/render/image/public/avatars/alice.png"
+        );
+        let mut query: Vec<_> = url.query_pairs().into_owned().collect();
+        query.sort();
+        assert_eq!(
+            query,
+            [
+                ("format", "origin"),
+                ("height", "100"),
+                ("quality", "70"),
+                ("resize", "contain"),
+                ("width", "200"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+        );
+
+        let width_only = TransformOptions {
+            width: Some(64),
+            ..Default::default()
+        };
+        assert_eq!(
+            client
+                .storage()
+                .get_public_url_with_transform("avatars", "a.png", &width_only),
+            "https://example.supabase.co/storage/v1/render/image/public/avatars/a.png?width=64"
+        );
+
+        // Without options the original object is served
+        assert_eq!(
+            client.storage().get_public_url_with_transform(
+                "avatars",
+                "a.png",
+                &TransformOptions::default()
+            ),
+            "https://example.supabase.co/storage/v1/object/public/avatars/a.png"
+        );
+    }
+
+    #[cfg(feature = "storage")]
+    #[test]
+    fn test_bucket_deserialize() {
+        let bucket: storage::Bucket = serde_json::from_str(
+            r#"{"id": "avatars", "name": "avatars", "owner": "", "public": true,
+                "file_size_limit": 1048576, "allowed_mime_types": ["image/*"],
+                "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert!(bucket.public);
+        assert_eq!(bucket.file_size_limit, Some(1048576));
+
+        let options = storage::BucketOptions {
+            public: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&options).unwrap(),
+            serde_json::json!({"public": true})
+        );
+    }
 }
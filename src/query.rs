@@ -0,0 +1,640 @@
+//! Query builder with typed execution.
+//!
+//! [`QueryBuilder`] wraps [`postgrest::Builder`], keeping its fluent API for
+//! filters and modifiers, and adds methods that execute the request and
+//! deserialize the response body with serde.
+
+use crate::error::{Error, Result};
+use crate::filter::Filter;
+use reqwest::header::{ACCEPT, CONTENT_RANGE, HeaderMap, HeaderName, HeaderValue};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// The `Prefer` request header.
+const PREFER: &str = "Prefer";
+
+/// How PostgREST counts the rows matching a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountMode {
+    /// Run `COUNT(*)`: accurate, but slow on large tables
+    Exact,
+    /// Use the query planner's estimate: fast, but approximate
+    Planned,
+    /// Exact for small results, planned above PostgREST's `db-max-rows`
+    Estimated,
+}
+
+impl CountMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Exact => "exact",
+            Self::Planned => "planned",
+            Self::Estimated => "estimated",
+        }
+    }
+}
+
+/// A page of rows with pagination metadata.
+///
+/// Returned by [`QueryBuilder::execute_page`].
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// The rows in this page
+    pub rows: Vec<T>,
+    /// Total number of matching rows, if a count was requested
+    pub total: Option<u64>,
+    /// Inclusive offsets of `rows` within the full result, `None` when empty
+    pub range: Option<(u64, u64)>,
+}
+
+impl<T> Page<T> {
+    /// Whether rows exist past this page.
+    ///
+    /// Always `false` when no count was requested.
+    pub fn has_more(&self) -> bool {
+        match (self.range, self.total) {
+            (Some((_, end)), Some(total)) => end + 1 < total,
+            _ => false,
+        }
+    }
+
+    /// Number of pages of `page_size` rows needed for the total.
+    pub fn page_count(&self, page_size: u64) -> Option<u64> {
+        self.total
+            .filter(|_| page_size > 0)
+            .map(|total| total.div_ceil(page_size))
+    }
+}
+
+/// Forward builder methods to the wrapped `postgrest::Builder`.
+macro_rules! delegate {
+    ($($(#[$doc:meta])* $name:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name(self, $($arg: $ty),*) -> Self {
+                self.map(|builder| builder.$name($($arg),*))
+            }
+        )*
+    };
+}
+
+/// A PostgREST query built from `SupabaseClient::from()` or `rpc()`.
+///
+/// All filters and modifiers from [`postgrest::Builder`] are available.
+/// Requests are sent with the client's HTTP settings (timeout, headers).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use supabase_client_rs::SupabaseClient;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = SupabaseClient::new("url", "key")?;
+/// #[derive(serde::Deserialize)]
+/// struct User {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// let users: Vec<User> = client
+///     .from("users")
+///     .select("id, name")
+///     .eq("status", "active")
+///     .execute_as()
+///     .await?;
+///
+/// let alice: User = client.from("users").select("*").eq("id", "1").single().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct QueryBuilder {
+    builder: postgrest::Builder,
+    http: reqwest::Client,
+    headers: HeaderMap,
+    prefer: Vec<String>,
+    params: Vec<(String, String)>,
+    columns: &'static [&'static str],
+}
+
+impl QueryBuilder {
+    /// Wrap a PostgREST builder, sending requests through `http`.
+    pub(crate) fn new(builder: postgrest::Builder, http: reqwest::Client) -> Self {
+        Self {
+            builder,
+            http,
+            headers: HeaderMap::new(),
+            prefer: Vec::new(),
+            params: Vec::new(),
+            columns: &[],
+        }
+    }
+
+    /// Set the columns selected by [`select_all`](Self::select_all).
+    pub(crate) fn with_columns(mut self, columns: &'static [&'static str]) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub(crate) fn map(mut self, f: impl FnOnce(postgrest::Builder) -> postgrest::Builder) -> Self {
+        self.builder = f(self.builder);
+        self
+    }
+
+    delegate! {
+        /// Authenticate this request with a JWT.
+        auth(token: impl AsRef<str>);
+        /// Select the given columns.
+        select(columns: impl Into<String>);
+        /// Order the result, e.g. `"created_at.desc"`.
+        order(columns: impl Into<String>);
+        /// Order the result on `columns`, optionally within a foreign table.
+        order_with_options(
+            columns: impl Into<String>,
+            foreign_table: Option<impl Into<String>>,
+            ascending: bool,
+            nulls_first: bool
+        );
+        /// Limit the result to `count` rows.
+        limit(count: usize);
+        /// Limit the rows of an embedded foreign table.
+        foreign_table_limit(count: usize, foreign_table: impl Into<String>);
+        /// Limit the result to rows `low` through `high` (inclusive).
+        range(low: usize, high: usize);
+        /// Request an exact count of the result.
+        exact_count();
+        /// Request a planner estimate of the result count.
+        planned_count();
+        /// Request an exact count for small results, a planned one otherwise.
+        estimated_count();
+        /// Insert the JSON `body`.
+        insert(body: impl Into<String>);
+        /// Upsert the JSON `body`, merging duplicates.
+        upsert(body: impl Into<String>);
+        /// Resolve upsert conflicts on the given unique columns.
+        on_conflict(columns: impl Into<String>);
+        /// Update matching rows with the JSON `body`.
+        update(body: impl Into<String>);
+        /// Delete matching rows.
+        delete();
+        /// Negate a filter, e.g. `not("eq", "status", "active")`.
+        not(operator: impl AsRef<str>, column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Match all of the comma-separated `filters`.
+        and(filters: impl AsRef<str>);
+        /// Match any of the comma-separated `filters`.
+        or(filters: impl AsRef<str>);
+        /// Filter rows where `column` equals `filter`.
+        eq(column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Filter rows where `column` does not equal `filter`.
+        neq(column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Filter rows where `column` is greater than `filter`.
+        gt(column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Filter rows where `column` is greater than or equal to `filter`.
+        gte(column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Filter rows where `column` is less than `filter`.
+        lt(column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Filter rows where `column` is less than or equal to `filter`.
+        lte(column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Filter rows where `column` matches `pattern` (case sensitive).
+        like(column: impl AsRef<str>, pattern: impl Into<String>);
+        /// Filter rows where `column` matches `pattern` (case insensitive).
+        ilike(column: impl AsRef<str>, pattern: impl Into<String>);
+        /// Filter rows where `column` is `null`, `true` or `false`.
+        is(column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Filter rows where `column` is one of `values`.
+        in_(column: impl AsRef<str>, values: impl IntoIterator<Item = impl AsRef<str>>);
+        /// Filter rows where `column` contains `filter`.
+        cs(column: impl AsRef<str>, filter: impl AsRef<str>);
+        /// Filter rows where `column` is contained by `filter`.
+        cd(column: impl Into<String>, filter: impl AsRef<str>);
+        /// Filter rows where the range `column` is strictly left of `range`.
+        sl(column: impl Into<String>, range: (i64, i64));
+        /// Filter rows where the range `column` is strictly right of `range`.
+        sr(column: impl Into<String>, range: (i64, i64));
+        /// Filter rows where the range `column` does not extend left of `range`.
+        nxl(column: impl Into<String>, range: (i64, i64));
+        /// Filter rows where the range `column` does not extend right of `range`.
+        nxr(column: impl Into<String>, range: (i64, i64));
+        /// Filter rows where the range `column` is adjacent to `range`.
+        adj(column: impl Into<String>, range: (i64, i64));
+        /// Filter rows where `column` overlaps `filter`.
+        ov(column: impl Into<String>, filter: impl AsRef<str>);
+        /// Full-text search with `to_tsquery`.
+        fts(column: impl Into<String>, tsquery: impl AsRef<str>, config: Option<&str>);
+        /// Full-text search with `plainto_tsquery`.
+        plfts(column: impl Into<String>, tsquery: impl AsRef<str>, config: Option<&str>);
+        /// Full-text search with `phraseto_tsquery`.
+        phfts(column: impl Into<String>, tsquery: impl AsRef<str>, config: Option<&str>);
+        /// Full-text search with `websearch_to_tsquery`.
+        wfts(column: impl Into<String>, tsquery: impl AsRef<str>, config: Option<&str>);
+    }
+
+    /// Insert `value`, serialized as JSON.
+    ///
+    /// Accepts a single row or a slice of rows. The inserted rows are
+    /// returned, so the query can be executed with `execute_as::<Vec<T>>()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// #[derive(serde::Serialize, serde::Deserialize)]
+    /// struct User {
+    ///     name: String,
+    ///     email: String,
+    /// }
+    ///
+    /// let alice = User { name: "Alice".into(), email: "alice@example.com".into() };
+    /// let inserted: Vec<User> = client
+    ///     .from("users")
+    ///     .insert_json(&alice)?
+    ///     .execute_as()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_json<T: Serialize + ?Sized>(self, value: &T) -> Result<Self> {
+        let body = serde_json::to_string(value)?;
+        Ok(self.insert(body))
+    }
+
+    /// Update matching rows with `value`, serialized as JSON.
+    ///
+    /// The updated rows are returned.
+    pub fn update_json<T: Serialize + ?Sized>(self, value: &T) -> Result<Self> {
+        let body = serde_json::to_string(value)?;
+        Ok(self.update(body))
+    }
+
+    /// Upsert `rows`, serialized as JSON.
+    ///
+    /// Duplicates are merged unless [`ignore_duplicates`](Self::ignore_duplicates)
+    /// is set. Combine with [`on_conflict`](Self::on_conflict) to resolve
+    /// conflicts on a unique index other than the primary key. The written
+    /// rows are returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// # let rows: Vec<serde_json::Value> = vec![];
+    /// let written: Vec<serde_json::Value> = client
+    ///     .from("users")
+    ///     .upsert_json(&rows)?
+    ///     .on_conflict("email")
+    ///     .ignore_duplicates()
+    ///     .execute_as()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn upsert_json<T: Serialize>(self, rows: &[T]) -> Result<Self> {
+        let body = serde_json::to_string(rows)?;
+        Ok(self.upsert(body))
+    }
+
+    /// Count the matching rows with `mode`.
+    ///
+    /// The count is reported by [`execute_page`](Self::execute_page). Unlike
+    /// `exact_count()`, this keeps the requested range intact.
+    pub fn count(self, mode: CountMode) -> Self {
+        self.prefer(format!("count={}", mode.as_str()))
+    }
+
+    /// Fetch the rows `offset..offset + limit`.
+    pub fn paginate(self, offset: usize, limit: usize) -> Self {
+        self.range(offset, offset + limit.max(1) - 1)
+    }
+
+    /// Skip conflicting rows during an upsert instead of merging them.
+    pub fn ignore_duplicates(self) -> Self {
+        self.prefer("resolution=ignore-duplicates")
+    }
+
+    /// Select every column of the table type.
+    ///
+    /// Selects the columns of the [`SupabaseTable`](crate::SupabaseTable)
+    /// when built with [`SupabaseClient::table`](crate::SupabaseClient::table),
+    /// and `*` otherwise.
+    pub fn select_all(self) -> Self {
+        let columns = match self.columns {
+            [] => "*".to_string(),
+            columns => columns.join(","),
+        };
+        self.select(columns)
+    }
+
+    /// Apply a typed [`Filter`].
+    ///
+    /// Can be called several times; all filters must match.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use supabase_client_rs::Filter;
+    ///
+    /// let posts = client
+    ///     .from("posts")
+    ///     .select("*")
+    ///     .filter(Filter::contains("tags", ["rust"]))
+    ///     .filter(Filter::eq("author_id", 42).or(Filter::eq("featured", true)))
+    ///     .execute()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn filter(self, filter: Filter) -> Self {
+        let (name, value) = filter.to_param();
+        self.param(name, value)
+    }
+
+    /// Run the write and roll it back.
+    ///
+    /// Sends `Prefer: tx=rollback` together with `return=representation`, so
+    /// the response holds the rows the statement would have affected while
+    /// nothing is committed. The PostgREST server must allow transaction
+    /// overrides (`db-tx-end = commit-allow-override`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// let would_delete: Vec<serde_json::Value> = client
+    ///     .from("sessions")
+    ///     .delete()
+    ///     .lt("expires_at", "2024-01-01")
+    ///     .dry_run()
+    ///     .execute_as()
+    ///     .await?;
+    /// println!("would delete {} sessions", would_delete.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dry_run(self) -> Self {
+        self.prefer("tx=rollback").prefer("return=representation")
+    }
+
+    /// Abort the write if it would affect more than `rows` rows.
+    ///
+    /// The transaction is rolled back and the request fails with
+    /// [`PostgrestErrorCode::MaxAffectedExceeded`](crate::PostgrestErrorCode::MaxAffectedExceeded).
+    /// Requires PostgREST 12 or later.
+    pub fn max_affected(self, rows: usize) -> Self {
+        self.prefer("handling=strict")
+            .prefer(format!("max-affected={}", rows))
+    }
+
+    /// Set a header on this request, replacing any previous value.
+    pub(crate) fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Append a query string parameter.
+    pub(crate) fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Add a `Prefer` preference, replacing any preference with the same key.
+    pub(crate) fn prefer(mut self, preference: impl Into<String>) -> Self {
+        self.prefer.push(preference.into());
+        self
+    }
+
+    /// Build the HTTP request without sending it.
+    pub fn build(self) -> Result<reqwest::Request> {
+        // postgrest builds on an older reqwest, so carry its request over
+        // to the client's own HTTP stack.
+        let source = self
+            .builder
+            .build()
+            .build()
+            .map_err(|e| Error::config(format!("invalid request: {}", e)))?;
+        let method = reqwest::Method::from_bytes(source.method().as_str().as_bytes())
+            .map_err(|e| Error::config(e.to_string()))?;
+
+        let mut request = self.http.request(method, source.url().as_str());
+        for (name, value) in source.headers() {
+            request = request.header(name.as_str(), value.as_bytes());
+        }
+        if let Some(body) = source.body().and_then(|b| b.as_bytes()) {
+            request = request.body(body.to_vec());
+        }
+
+        let mut request = request.build()?;
+        for (name, value) in self.headers {
+            if let Some(name) = name {
+                request.headers_mut().insert(name, value);
+            }
+        }
+
+        if !self.params.is_empty() {
+            request
+                .url_mut()
+                .query_pairs_mut()
+                .extend_pairs(&self.params);
+        }
+
+        if !self.prefer.is_empty() {
+            let current = request
+                .headers()
+                .get(PREFER)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            let value = merge_preferences(current, &self.prefer);
+            let value = HeaderValue::from_str(&value).map_err(|e| Error::config(e.to_string()))?;
+            request.headers_mut().insert(PREFER, value);
+        }
+        Ok(request)
+    }
+
+    /// Get the wrapped `postgrest::Builder`.
+    pub fn into_inner(self) -> postgrest::Builder {
+        self.builder
+    }
+
+    /// Send the request and return the raw response.
+    ///
+    /// Non-2xx responses are returned as [`Error::PostgREST`].
+    pub async fn execute(self) -> Result<reqwest::Response> {
+        let http = self.http.clone();
+        let request = self.build()?;
+        check_response(http.execute(request).await?).await
+    }
+
+    /// Send the request and deserialize the response body into `T`.
+    ///
+    /// Non-2xx responses are returned as [`Error::PostgREST`].
+    pub async fn execute_as<T: DeserializeOwned>(self) -> Result<T> {
+        let body = self.execute().await?.text().await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Send the request and return the rows with pagination metadata.
+    ///
+    /// Combine with [`count`](Self::count) to get the total number of rows.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use supabase_client_rs::CountMode;
+    ///
+    /// let page = client
+    ///     .from("users")
+    ///     .select("*")
+    ///     .order("id")
+    ///     .paginate(50, 25)
+    ///     .count(CountMode::Exact)
+    ///     .execute_page::<serde_json::Value>()
+    ///     .await?;
+    /// println!("{} of {:?} users", page.rows.len(), page.total);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_page<T: DeserializeOwned>(self) -> Result<Page<T>> {
+        let response = self.execute().await?;
+        let (range, total) = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .map(parse_content_range)
+            .unwrap_or_default();
+        let body = response.text().await?;
+        Ok(Page {
+            rows: serde_json::from_str(&body)?,
+            total,
+            range,
+        })
+    }
+
+    /// Fetch exactly one row.
+    ///
+    /// Fails with [`Error::PostgREST`] when zero or several rows match.
+    pub async fn single<T: DeserializeOwned>(self) -> Result<T> {
+        self.header(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.pgrst.object+json"),
+        )
+        .execute_as()
+        .await
+    }
+
+    /// Fetch at most one row.
+    ///
+    /// Returns `None` when no row matches and fails with [`Error::PostgREST`]
+    /// when several rows match. Like [`single`](Self::single), the server
+    /// checks the row count, so a loose filter never loads a whole table.
+    pub async fn maybe_single<T: DeserializeOwned>(self) -> Result<Option<T>> {
+        match self.single().await {
+            Ok(row) => Ok(Some(row)),
+            Err(e) if is_no_rows(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl std::fmt::Debug for QueryBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryBuilder").finish_non_exhaustive()
+    }
+}
+
+/// Whether `error` is PostgREST refusing a single object because no row
+/// matched (`PGRST116` with details such as "The result contains 0 rows").
+fn is_no_rows(error: &Error) -> bool {
+    match error {
+        Error::PostgREST {
+            code: Some(code),
+            details: Some(details),
+            ..
+        } if code == "PGRST116" => {
+            let words: Vec<&str> = details.split_whitespace().collect();
+            words.windows(2).any(|w| w == ["0", "rows"])
+        }
+        _ => false,
+    }
+}
+
+/// Parse a `Content-Range` header such as `0-24/3573`, `*/0` or `0-24/*`.
+fn parse_content_range(value: &str) -> (Option<(u64, u64)>, Option<u64>) {
+    let (range, total) = value.split_once('/').unwrap_or((value, "*"));
+    let range = range
+        .split_once('-')
+        .and_then(|(start, end)| Some((start.trim().parse().ok()?, end.trim().parse().ok()?)));
+    (range, total.trim().parse().ok())
+}
+
+/// Merge `extra` preferences into a `Prefer` header value.
+///
+/// A preference replaces an existing one with the same key, so
+/// `resolution=ignore-duplicates` overrides `resolution=merge-duplicates`.
+fn merge_preferences(current: &str, extra: &[String]) -> String {
+    let key = |p: &str| p.split('=').next().unwrap_or_default().trim().to_string();
+
+    let mut merged: Vec<String> = current
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect();
+    for preference in extra {
+        merged.retain(|p| key(p) != key(preference));
+        merged.push(preference.clone());
+    }
+    merged.join(",")
+}
+
+/// Pass 2xx responses through and decode the error payload of the rest.
+pub(crate) async fn check_response(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await?;
+    Err(Error::postgrest_response(status.as_u16(), &body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(
+            parse_content_range("0-24/3573"),
+            (Some((0, 24)), Some(3573))
+        );
+        assert_eq!(parse_content_range("0-24/*"), (Some((0, 24)), None));
+        assert_eq!(parse_content_range("*/0"), (None, Some(0)));
+    }
+
+    #[test]
+    fn test_is_no_rows() {
+        let none = Error::postgrest_response(
+            406,
+            r#"{"code":"PGRST116","details":"The result contains 0 rows","hint":null,"message":"Cannot coerce the result to a single JSON object"}"#,
+        );
+        assert!(is_no_rows(&none));
+
+        let many = Error::postgrest_response(
+            406,
+            r#"{"code":"PGRST116","details":"The result contains 20 rows","hint":null,"message":"Cannot coerce the result to a single JSON object"}"#,
+        );
+        assert!(!is_no_rows(&many));
+
+        let other = Error::postgrest_response(404, r#"{"code":"42P01","details":"0 rows"}"#);
+        assert!(!is_no_rows(&other));
+    }
+}
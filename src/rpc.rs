@@ -0,0 +1,122 @@
+//! Typed stored procedure calls.
+
+use crate::error::{Error, Result};
+use crate::filter::{Filter, argument};
+use crate::query::QueryBuilder;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::marker::PhantomData;
+
+/// A stored procedure call returning `R`.
+///
+/// Created by [`SupabaseClient::rpc_typed`](crate::SupabaseClient::rpc_typed).
+/// Filters and modifiers apply to the rows of set-returning functions.
+#[derive(Debug)]
+pub struct RpcBuilder<R> {
+    query: QueryBuilder,
+    args: Map<String, Value>,
+    get: bool,
+    _returns: PhantomData<fn() -> R>,
+}
+
+impl<R: DeserializeOwned> RpcBuilder<R> {
+    pub(crate) fn new(query: QueryBuilder, args: &impl Serialize) -> Result<Self> {
+        let args = match serde_json::to_value(args)? {
+            Value::Object(args) => args,
+            Value::Null => Map::new(),
+            _ => return Err(Error::config("RPC arguments must serialize to an object")),
+        };
+        Ok(Self {
+            query,
+            args,
+            get: false,
+            _returns: PhantomData,
+        })
+    }
+
+    fn map(mut self, f: impl FnOnce(QueryBuilder) -> QueryBuilder) -> Self {
+        self.query = f(self.query);
+        self
+    }
+
+    /// Call the function with `GET` instead of `POST`.
+    ///
+    /// Only allowed for `STABLE` or `IMMUTABLE` functions. Arguments are sent
+    /// in the query string, which makes the call cacheable and lets it run
+    /// in a read-only transaction.
+    pub fn get(mut self) -> Self {
+        self.get = true;
+        self
+    }
+
+    /// Apply a typed [`Filter`] to the returned rows.
+    pub fn filter(self, filter: Filter) -> Self {
+        self.map(|query| query.filter(filter))
+    }
+
+    /// Select the given columns of the returned rows.
+    pub fn select(self, columns: impl Into<String>) -> Self {
+        self.map(|query| query.select(columns))
+    }
+
+    /// Order the returned rows.
+    pub fn order(self, columns: impl Into<String>) -> Self {
+        self.map(|query| query.order(columns))
+    }
+
+    /// Limit the number of returned rows.
+    pub fn limit(self, count: usize) -> Self {
+        self.map(|query| query.limit(count))
+    }
+
+    /// Return only the rows `low..=high`.
+    pub fn range(self, low: usize, high: usize) -> Self {
+        self.map(|query| query.range(low, high))
+    }
+
+    /// Finish the call as a [`QueryBuilder`], e.g. to use its other filters.
+    pub fn into_query(self) -> QueryBuilder {
+        if self.get {
+            self.args.iter().fold(self.query, |query, (name, value)| {
+                query.param(name.as_str(), argument(value))
+            })
+        } else {
+            let body = Value::Object(self.args).to_string();
+            self.query.map(|builder| builder.rpc(body))
+        }
+    }
+
+    /// Call the function and deserialize its return value.
+    ///
+    /// Functions returning `void` answer with an empty body (`204 No
+    /// Content` on recent PostgREST), which is read as `null`, so `R` can
+    /// be `()` or an `Option`.
+    pub async fn execute(self) -> Result<R> {
+        let body = self.into_query().execute().await?.text().await?;
+        parse_return(&body)
+    }
+}
+
+/// Deserialize a function's return value, reading an empty body as `null`.
+fn parse_return<R: DeserializeOwned>(body: &str) -> Result<R> {
+    if body.trim().is_empty() {
+        return Ok(R::deserialize(Value::Null)?);
+    }
+    Ok(serde_json::from_str(body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_return() {
+        parse_return::<()>("").unwrap();
+        parse_return::<()>("null").unwrap();
+        assert_eq!(parse_return::<Option<i64>>("").unwrap(), None);
+        assert_eq!(parse_return::<Option<i64>>("42").unwrap(), Some(42));
+        assert_eq!(parse_return::<Vec<i64>>("[1, 2]").unwrap(), [1, 2]);
+        assert!(parse_return::<i64>("").is_err());
+    }
+}
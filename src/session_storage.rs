@@ -0,0 +1,128 @@
+//! Built-in [`SessionStorage`] implementations.
+//!
+//! Available without any feature, so both auth sessions and resumable
+//! Storage upload URLs can be persisted with them.
+
+use crate::error::Result;
+use crate::traits::{SessionStorage, async_trait};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::io::AsyncWriteExt;
+
+/// Distinguishes temporary files of concurrent writes in this process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Keeps values in memory for the lifetime of the process.
+///
+/// This is the default session storage when none is configured.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    items: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryStorage {
+    /// Create an empty in-memory storage.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStorage for MemoryStorage {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        let items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(items.get(key).cloned())
+    }
+
+    async fn set(&self, key: &str, value: &str) -> Result<()> {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        items.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        items.remove(key);
+        Ok(())
+    }
+}
+
+/// Persists values as files in a directory, one file per key.
+///
+/// Use this to keep CLI tools signed in across invocations, or to resume
+/// Storage uploads after a restart. Files may hold refresh tokens, so on
+/// Unix they are created readable by the owner only (mode `0600`). Writes go
+/// to a temporary file that is renamed into place, so a crash never leaves a
+/// truncated value behind.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use supabase_client_rs::{FileStorage, SupabaseConfig};
+///
+/// let config = SupabaseConfig::new("https://your-project.supabase.co", "your-anon-key")
+///     .session_storage(FileStorage::new("/home/me/.config/my-cli"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    /// Store values in `dir`, creating it on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+#[async_trait]
+impl SessionStorage for FileStorage {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        match tokio::fs::read_to_string(self.path(key)).await {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn set(&self, key: &str, value: &str) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let temp = self.dir.join(format!(
+            ".{}.json.{}-{}.tmp",
+            key,
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        let written = async {
+            let mut file = options.open(&temp).await?;
+            file.write_all(value.as_bytes()).await?;
+            file.sync_all().await?;
+            tokio::fs::rename(&temp, self.path(key)).await
+        }
+        .await;
+        if let Err(e) = written {
+            let _ = tokio::fs::remove_file(&temp).await;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path(key)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
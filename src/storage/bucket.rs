@@ -0,0 +1,153 @@
+//! Bucket management.
+
+use super::{StorageClient, check_response, handle_response};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+
+/// A storage bucket.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Bucket {
+    /// Bucket identifier
+    pub id: String,
+    /// Bucket name
+    pub name: String,
+    /// Owner of the bucket
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Whether files can be read without authorization
+    #[serde(default)]
+    pub public: bool,
+    /// Maximum file size in bytes
+    #[serde(default)]
+    pub file_size_limit: Option<u64>,
+    /// MIME types accepted for upload (e.g. `image/*`)
+    #[serde(default)]
+    pub allowed_mime_types: Option<Vec<String>>,
+    /// Timestamp of creation
+    #[serde(default)]
+    pub created_at: Option<String>,
+    /// Timestamp of last update
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+/// Settings for creating or updating a bucket.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BucketOptions {
+    /// Whether files can be read without authorization
+    pub public: bool,
+    /// Maximum file size in bytes (default: the project's global limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size_limit: Option<u64>,
+    /// MIME types accepted for upload, wildcards allowed (default: any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_mime_types: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct BucketRequest<'a> {
+    id: &'a str,
+    name: &'a str,
+    #[serde(flatten)]
+    options: &'a BucketOptions,
+}
+
+#[derive(Deserialize)]
+struct CreatedBucket {
+    name: String,
+}
+
+impl StorageClient {
+    /// List all buckets.
+    pub async fn list_buckets(&self) -> Result<Vec<Bucket>> {
+        let response = self
+            .http()
+            .get(format!("{}/bucket", self.url))
+            .send()
+            .await?;
+        handle_response(response).await
+    }
+
+    /// Get a bucket by id.
+    pub async fn get_bucket(&self, id: &str) -> Result<Bucket> {
+        let response = self.http().get(self.url("bucket", id, "")?).send().await?;
+        handle_response(response).await
+    }
+
+    /// Create a bucket and return its name.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use supabase_client_rs::storage::BucketOptions;
+    ///
+    /// client
+    ///     .storage()
+    ///     .create_bucket(
+    ///         "tenant-42-images",
+    ///         &BucketOptions {
+    ///             public: false,
+    ///             file_size_limit: Some(10 * 1024 * 1024),
+    ///             allowed_mime_types: Some(vec!["image/*".to_string()]),
+    ///         },
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_bucket(&self, id: &str, options: &BucketOptions) -> Result<String> {
+        let response = self
+            .http()
+            .post(format!("{}/bucket", self.url))
+            .json(&BucketRequest {
+                id,
+                name: id,
+                options,
+            })
+            .send()
+            .await?;
+        let created: CreatedBucket = handle_response(response).await?;
+        Ok(created.name)
+    }
+
+    /// Replace the settings of a bucket.
+    pub async fn update_bucket(&self, id: &str, options: &BucketOptions) -> Result<()> {
+        let response = self
+            .http()
+            .put(self.url("bucket", id, "")?)
+            .json(&BucketRequest {
+                id,
+                name: id,
+                options,
+            })
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+
+    /// Delete every file in a bucket.
+    pub async fn empty_bucket(&self, id: &str) -> Result<()> {
+        let response = self
+            .http()
+            .post(self.url("bucket", id, "empty")?)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+
+    /// Delete a bucket. It must be empty.
+    pub async fn delete_bucket(&self, id: &str) -> Result<()> {
+        let response = self
+            .http()
+            .delete(self.url("bucket", id, "")?)
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+//! Built-in Supabase Storage client.
+//!
+//! Requires the `storage` feature. The client talks to the Storage API at
+//! [`SupabaseConfig::storage_url`](crate::SupabaseConfig::storage_url) and
+//! sends requests with the same HTTP client and auth headers as
+//! [`SupabaseClient`](crate::SupabaseClient), so signing in through the auth
+//! client also authorizes storage requests.
+
+mod bucket;
+mod resumable;
+mod stream;
+mod transform;
+
+pub use bucket::{Bucket, BucketOptions};
+pub use resumable::{DEFAULT_CHUNK_SIZE, PauseHandle, ResumableUpload, UploadStatus};
+pub use transform::{ImageFormat, Resize, TransformOptions};
+
+use crate::client::Transport;
+use crate::error::{Error, Result};
+use crate::traits::{StorageObject, StorageProvider, async_trait};
+use reqwest::header::{CACHE_CONTROL, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use url::Url;
+
+/// Options for uploading a file.
+#[derive(Debug, Clone, Default)]
+pub struct FileOptions {
+    /// MIME type of the file (default: detected by the server)
+    pub content_type: Option<String>,
+    /// `Cache-Control` max-age in seconds (default: 3600)
+    pub cache_control: Option<u64>,
+    /// Overwrite an existing file instead of failing
+    pub upsert: bool,
+}
+
+/// Options for listing files.
+#[derive(Debug, Clone, Serialize)]
+pub struct ListOptions {
+    /// Maximum number of files returned
+    pub limit: u32,
+    /// Number of files to skip
+    pub offset: u32,
+    /// Only return files whose name contains this string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    /// Sort order
+    #[serde(rename = "sortBy")]
+    pub sort_by: SortBy,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            limit: 100,
+            offset: 0,
+            search: None,
+            sort_by: SortBy::default(),
+        }
+    }
+}
+
+/// Sort order for [`ListOptions`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SortBy {
+    /// Column to sort by (`name`, `updated_at`, `created_at`, `last_accessed_at`)
+    pub column: String,
+    /// `asc` or `desc`
+    pub order: String,
+}
+
+impl Default for SortBy {
+    fn default() -> Self {
+        Self {
+            column: "name".to_string(),
+            order: "asc".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    #[serde(rename = "Key")]
+    key: String,
+}
+
+#[derive(Deserialize)]
+struct SignedUrlResponse {
+    #[serde(rename = "signedURL")]
+    signed_url: String,
+}
+
+#[derive(Deserialize)]
+struct StorageErrorBody {
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Client for the Supabase Storage API.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use supabase_client_rs::SupabaseClient;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = SupabaseClient::new("url", "key")?;
+/// let storage = client.storage();
+///
+/// let key = storage
+///     .upload("avatars", "alice.png", std::fs::read("alice.png")?, Some("image/png"))
+///     .await?;
+/// let data = storage.download("avatars", "alice.png").await?;
+/// let url = storage.create_signed_url("avatars", "alice.png", 3600).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct StorageClient {
+    url: String,
+    transport: Arc<RwLock<Transport>>,
+}
+
+impl StorageClient {
+    /// Create a storage client for `url` sending requests through `transport`.
+    pub(crate) fn new(url: String, transport: Arc<RwLock<Transport>>) -> Self {
+        Self { url, transport }
+    }
+
+    /// The HTTP client carrying the current auth headers.
+    fn http(&self) -> reqwest::Client {
+        self.transport
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .http
+            .clone()
+    }
+
+    /// Build `<storage_url>/<prefix>/<bucket>/<path>`, percent-encoding each
+    /// segment of `path`.
+    fn url(&self, prefix: &str, bucket: &str, path: &str) -> Result<Url> {
+        let mut url = Url::parse(&self.url)?;
+        url.path_segments_mut()
+            .map_err(|_| Error::config("storage URL cannot be a base"))?
+            .pop_if_empty()
+            .extend(prefix.split('/'))
+            .push(bucket)
+            .extend(path.split('/').filter(|s| !s.is_empty()));
+        Ok(url)
+    }
+
+    /// Upload a file.
+    ///
+    /// Returns the object key (`<bucket>/<path>`). Fails if the file exists;
+    /// use [`upload_with_options`](Self::upload_with_options) to overwrite.
+    pub async fn upload(
+        &self,
+        bucket: &str,
+        path: &str,
+        data: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<String> {
+        let options = FileOptions {
+            content_type: content_type.map(String::from),
+            ..Default::default()
+        };
+        self.upload_with_options(bucket, path, data, &options).await
+    }
+
+    /// Upload a file with cache and overwrite options.
+    pub async fn upload_with_options(
+        &self,
+        bucket: &str,
+        path: &str,
+        data: Vec<u8>,
+        options: &FileOptions,
+    ) -> Result<String> {
+        let request = self.http().post(self.url("object", bucket, path)?);
+        let response = with_file_options(request, options)
+            .body(data)
+            .send()
+            .await?;
+        let uploaded: UploadResponse = handle_response(response).await?;
+        Ok(uploaded.key)
+    }
+
+    /// Download a file.
+    pub async fn download(&self, bucket: &str, path: &str) -> Result<Vec<u8>> {
+        let response = self
+            .http()
+            .get(self.url("object", bucket, path)?)
+            .send()
+            .await?;
+        Ok(check_response(response).await?.bytes().await?.to_vec())
+    }
+
+    /// Delete files.
+    pub async fn remove(&self, bucket: &str, paths: &[&str]) -> Result<()> {
+        let response = self
+            .http()
+            .delete(self.url("object", bucket, "")?)
+            .json(&serde_json::json!({ "prefixes": paths }))
+            .send()
+            .await?;
+        check_response(response).await?;
+        Ok(())
+    }
+
+    /// List the files and folders under `path` (the bucket root if `None`).
+    pub async fn list(&self, bucket: &str, path: Option<&str>) -> Result<Vec<StorageObject>> {
+        self.list_with_options(bucket, path, &ListOptions::default())
+            .await
+    }
+
+    /// List files with paging, search and sort options.
+    pub async fn list_with_options(
+        &self,
+        bucket: &str,
+        path: Option<&str>,
+        options: &ListOptions,
+    ) -> Result<Vec<StorageObject>> {
+        let mut body = serde_json::to_value(options)?;
+        body["prefix"] = path.unwrap_or_default().into();
+        let response = self
+            .http()
+            .post(self.url("object/list", bucket, "")?)
+            .json(&body)
+            .send()
+            .await?;
+        handle_response(response).await
+    }
+
+    /// Get the URL of a file in a public bucket.
+    ///
+    /// No request is made; the URL only works if the bucket is public.
+    pub fn get_public_url(&self, bucket: &str, path: &str) -> String {
+        self.url("object/public", bucket, path)
+            .map(String::from)
+            .unwrap_or_else(|_| format!("{}/object/public/{}/{}", self.url, bucket, path))
+    }
+
+    /// Create a URL granting access to a file for `expires_in` seconds.
+    pub async fn create_signed_url(
+        &self,
+        bucket: &str,
+        path: &str,
+        expires_in: u64,
+    ) -> Result<String> {
+        let response = self
+            .http()
+            .post(self.url("object/sign", bucket, path)?)
+            .json(&serde_json::json!({ "expiresIn": expires_in }))
+            .send()
+            .await?;
+        let signed: SignedUrlResponse = handle_response(response).await?;
+        Ok(format!("{}{}", self.url, signed.signed_url))
+    }
+}
+
+#[async_trait]
+impl StorageProvider for StorageClient {
+    async fn upload(
+        &self,
+        bucket: &str,
+        path: &str,
+        data: Vec<u8>,
+        content_type: Option<&str>,
+    ) -> Result<String> {
+        StorageClient::upload(self, bucket, path, data, content_type).await
+    }
+
+    async fn download(&self, bucket: &str, path: &str) -> Result<Vec<u8>> {
+        StorageClient::download(self, bucket, path).await
+    }
+
+    async fn remove(&self, bucket: &str, paths: &[&str]) -> Result<()> {
+        StorageClient::remove(self, bucket, paths).await
+    }
+
+    async fn list(&self, bucket: &str, path: Option<&str>) -> Result<Vec<StorageObject>> {
+        StorageClient::list(self, bucket, path).await
+    }
+
+    fn get_public_url(&self, bucket: &str, path: &str) -> String {
+        StorageClient::get_public_url(self, bucket, path)
+    }
+
+    async fn create_signed_url(&self, bucket: &str, path: &str, expires_in: u64) -> Result<String> {
+        StorageClient::create_signed_url(self, bucket, path, expires_in).await
+    }
+}
+
+impl std::fmt::Debug for StorageClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageClient")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Apply upload options as request headers.
+fn with_file_options(
+    request: reqwest::RequestBuilder,
+    options: &FileOptions,
+) -> reqwest::RequestBuilder {
+    let cache_control = format!("max-age={}", options.cache_control.unwrap_or(3600));
+    let request = request
+        .header(CACHE_CONTROL, cache_control)
+        .header("x-upsert", options.upsert.to_string());
+    match &options.content_type {
+        Some(content_type) => request.header(CONTENT_TYPE, content_type),
+        None => request,
+    }
+}
+
+/// Turn a non-2xx response into [`Error::Storage`].
+async fn check_response(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await?;
+    let message = serde_json::from_str::<StorageErrorBody>(&body)
+        .ok()
+        .and_then(|e| e.message.or(e.error))
+        .unwrap_or_else(|| format!("request failed with status {}", status));
+    Err(Error::Storage(message))
+}
+
+/// Check the response status and deserialize the body.
+async fn handle_response<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    let body = check_response(response).await?.text().await?;
+    Ok(serde_json::from_str(&body)?)
+}
@@ -0,0 +1,404 @@
+//! Resumable uploads over the TUS protocol.
+//!
+//! Files are sent in fixed-size chunks to `<storage_url>/upload/resumable`.
+//! The server remembers how much it received, so an interrupted upload can
+//! continue from the last chunk instead of starting over.
+
+use super::{FileOptions, StorageClient};
+use crate::error::{Error, Result};
+use crate::traits::SessionStorage;
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD as BASE64_URL};
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, LOCATION};
+use std::io::SeekFrom;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
+
+/// TUS protocol version spoken by Supabase Storage.
+const TUS_VERSION: &str = "1.0.0";
+
+/// Chunk size required by Supabase Storage (6 MiB).
+pub const DEFAULT_CHUNK_SIZE: usize = 6 * 1024 * 1024;
+
+/// Callback invoked with the bytes uploaded so far and the total size.
+type ProgressCallback = Box<dyn Fn(u64, u64) + Send + Sync>;
+
+/// Outcome of [`ResumableUpload::start`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadStatus {
+    /// The whole file was uploaded
+    Completed,
+    /// The upload was paused after `offset` bytes; call `start` to resume
+    Paused {
+        /// Bytes stored on the server
+        offset: u64,
+    },
+}
+
+/// Pauses a running [`ResumableUpload`] after its current chunk.
+#[derive(Debug, Clone, Default)]
+pub struct PauseHandle(Arc<AtomicBool>);
+
+impl PauseHandle {
+    /// Ask the upload to stop after the chunk in flight.
+    pub fn pause(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A resumable upload of `len` bytes.
+///
+/// Created by [`StorageClient::resumable_upload`]. Calling
+/// [`start`](Self::start) again after a pause, an error or a restart (with
+/// [`url_storage`](Self::url_storage)) continues from the last stored chunk.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use supabase_client_rs::SupabaseClient;
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = SupabaseClient::new("url", "key")?;
+/// use supabase_client_rs::FileStorage;
+///
+/// let mut file = tokio::fs::File::open("backup.tar").await?;
+/// let len = file.metadata().await?.len();
+///
+/// let upload = client
+///     .storage()
+///     .resumable_upload("backups", "2024/backup.tar", len)
+///     .url_storage(FileStorage::new("/var/lib/my-app/uploads"))
+///     .on_progress(|sent, total| println!("{}/{} bytes", sent, total));
+///
+/// // Retry a few times, resuming from the last stored chunk each time
+/// let mut attempts = 0;
+/// loop {
+///     match upload.start(&mut file).await {
+///         Ok(_) => break,
+///         Err(e) if attempts < 5 => {
+///             attempts += 1;
+///             eprintln!("upload failed ({}), retrying", e);
+///             tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+///         }
+///         Err(e) => return Err(e.into()),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ResumableUpload {
+    client: StorageClient,
+    bucket: String,
+    path: String,
+    len: u64,
+    options: FileOptions,
+    chunk_size: usize,
+    url: Mutex<Option<String>>,
+    url_storage: Option<Arc<dyn SessionStorage>>,
+    on_progress: Option<ProgressCallback>,
+    paused: PauseHandle,
+}
+
+impl StorageClient {
+    /// Prepare a resumable upload of `len` bytes to `bucket`/`path`.
+    pub fn resumable_upload(&self, bucket: &str, path: &str, len: u64) -> ResumableUpload {
+        ResumableUpload {
+            client: self.clone(),
+            bucket: bucket.to_string(),
+            path: path.to_string(),
+            len,
+            options: FileOptions::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            url: Mutex::new(None),
+            url_storage: None,
+            on_progress: None,
+            paused: PauseHandle::default(),
+        }
+    }
+}
+
+impl ResumableUpload {
+    /// Set the content type, cache control and overwrite options.
+    pub fn options(mut self, options: FileOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the chunk size (default: 6 MiB, which Supabase Storage requires).
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Continue an upload created earlier at `url`.
+    pub fn upload_url(self, url: impl Into<String>) -> Self {
+        *self.url.lock().unwrap_or_else(|e| e.into_inner()) = Some(url.into());
+        self
+    }
+
+    /// Persist the upload URL in `storage`, so a new process can resume.
+    ///
+    /// The URL is keyed by bucket, path and length, and removed once the
+    /// upload completes. [`FileStorage`](crate::FileStorage) keeps it on
+    /// disk.
+    pub fn url_storage(mut self, storage: impl SessionStorage + 'static) -> Self {
+        self.url_storage = Some(Arc::new(storage));
+        self
+    }
+
+    /// Call `callback(uploaded, total)` after every chunk.
+    pub fn on_progress(mut self, callback: impl Fn(u64, u64) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Get a handle that pauses the upload.
+    pub fn pause_handle(&self) -> PauseHandle {
+        self.paused.clone()
+    }
+
+    /// The upload URL, once the upload was created on the server.
+    pub fn url(&self) -> Option<String> {
+        self.url.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Upload the file read from `reader`, resuming if possible.
+    ///
+    /// `reader` must yield the same `len` bytes on every call; it is seeked
+    /// to the offset the server already has.
+    pub async fn start<R>(&self, reader: &mut R) -> Result<UploadStatus>
+    where
+        R: AsyncRead + AsyncSeek + Unpin,
+    {
+        self.paused.0.store(false, Ordering::SeqCst);
+
+        let (url, mut offset) = match self.resume().await? {
+            Some(resumed) => resumed,
+            None => (self.create().await?, 0),
+        };
+        self.report(offset);
+
+        reader.seek(SeekFrom::Start(offset)).await?;
+        while offset < self.len {
+            if self.paused.0.load(Ordering::SeqCst) {
+                return Ok(UploadStatus::Paused { offset });
+            }
+
+            let size = (self.len - offset).min(self.chunk_size as u64);
+            let mut chunk = Vec::with_capacity(size as usize);
+            (&mut *reader).take(size).read_to_end(&mut chunk).await?;
+            if chunk.is_empty() {
+                return Err(Error::Storage(format!(
+                    "reader ended after {} of {} bytes",
+                    offset, self.len
+                )));
+            }
+
+            offset = self.patch(&url, offset, chunk).await?;
+            self.report(offset);
+        }
+
+        if let Some(storage) = &self.url_storage {
+            storage.remove(&self.storage_key()).await?;
+        }
+        Ok(UploadStatus::Completed)
+    }
+
+    /// Find the upload URL and the server's offset for a previous attempt.
+    async fn resume(&self) -> Result<Option<(String, u64)>> {
+        let mut url = self.url();
+        if let (None, Some(storage)) = (&url, &self.url_storage) {
+            url = storage.get(&self.storage_key()).await?;
+        }
+        let Some(url) = url else {
+            return Ok(None);
+        };
+
+        let response = self
+            .client
+            .http()
+            .head(&url)
+            .header("Tus-Resumable", TUS_VERSION)
+            .send()
+            .await?;
+        match response.status() {
+            // Expired or unknown upload: start a new one
+            StatusCode::NOT_FOUND | StatusCode::GONE | StatusCode::FORBIDDEN => Ok(None),
+            status if status.is_success() => {
+                let offset = upload_offset(response.headers())?;
+                self.set_url(&url).await?;
+                Ok(Some((url, offset)))
+            }
+            status => Err(Error::Storage(format!(
+                "failed to resume upload: status {}",
+                status
+            ))),
+        }
+    }
+
+    /// Create the upload on the server and return its URL.
+    async fn create(&self) -> Result<String> {
+        let response = self
+            .client
+            .http()
+            .post(format!("{}/upload/resumable", self.client.url))
+            .header("Tus-Resumable", TUS_VERSION)
+            .header("Upload-Length", self.len)
+            .header("Upload-Metadata", self.metadata())
+            .header("x-upsert", self.options.upsert.to_string())
+            .send()
+            .await?;
+        let response = super::check_response(response).await?;
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| Error::Storage("upload created without a location".to_string()))?;
+        let url = response.url().join(location)?.to_string();
+
+        self.set_url(&url).await?;
+        Ok(url)
+    }
+
+    /// The `Upload-Metadata` header: comma-separated keys with base64 values.
+    fn metadata(&self) -> String {
+        let content_type = self
+            .options
+            .content_type
+            .as_deref()
+            .unwrap_or("application/octet-stream");
+        let cache_control = self.options.cache_control.unwrap_or(3600).to_string();
+        [
+            ("bucketName", self.bucket.as_str()),
+            ("objectName", self.path.as_str()),
+            ("contentType", content_type),
+            ("cacheControl", cache_control.as_str()),
+        ]
+        .iter()
+        .map(|(key, value)| format!("{} {}", key, BASE64.encode(value)))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+
+    /// Send one chunk at `offset` and return the new offset.
+    async fn patch(&self, url: &str, offset: u64, chunk: Vec<u8>) -> Result<u64> {
+        let response = self
+            .client
+            .http()
+            .patch(url)
+            .header("Tus-Resumable", TUS_VERSION)
+            .header("Upload-Offset", offset)
+            .header(CONTENT_TYPE, "application/offset+octet-stream")
+            .body(chunk)
+            .send()
+            .await?;
+        let response = super::check_response(response).await?;
+        upload_offset(response.headers())
+    }
+
+    async fn set_url(&self, url: &str) -> Result<()> {
+        *self.url.lock().unwrap_or_else(|e| e.into_inner()) = Some(url.to_string());
+        if let Some(storage) = &self.url_storage {
+            storage.set(&self.storage_key(), url).await?;
+        }
+        Ok(())
+    }
+
+    /// Key under which the upload URL is stored.
+    ///
+    /// Bucket and path are base64url-encoded, so the key only holds
+    /// `[A-Za-z0-9_-]` plus the `.` separators and cannot collide.
+    fn storage_key(&self) -> String {
+        format!(
+            "tus-{}.{}.{}",
+            BASE64_URL.encode(&self.bucket),
+            BASE64_URL.encode(&self.path),
+            self.len
+        )
+    }
+
+    fn report(&self, offset: u64) {
+        if let Some(callback) = &self.on_progress {
+            callback(offset, self.len);
+        }
+    }
+}
+
+impl std::fmt::Debug for ResumableUpload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResumableUpload")
+            .field("bucket", &self.bucket)
+            .field("path", &self.path)
+            .field("len", &self.len)
+            .field("chunk_size", &self.chunk_size)
+            .field("url", &self.url())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Read the `Upload-Offset` header of a TUS response.
+fn upload_offset(headers: &HeaderMap) -> Result<u64> {
+    headers
+        .get("Upload-Offset")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| Error::Storage("missing Upload-Offset header".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SupabaseClient;
+    use reqwest::header::HeaderValue;
+
+    fn upload(path: &str) -> ResumableUpload {
+        upload_to("backups", path)
+    }
+
+    fn upload_to(bucket: &str, path: &str) -> ResumableUpload {
+        let client = SupabaseClient::new("https://example.supabase.co", "key").unwrap();
+        client.storage().resumable_upload(bucket, path, 1024)
+    }
+
+    #[test]
+    fn test_storage_key() {
+        assert_eq!(
+            upload("2024/backup v1.tar").storage_key(),
+            "tus-YmFja3Vwcw.MjAyNC9iYWNrdXAgdjEudGFy.1024"
+        );
+
+        // Paths that only differ in characters a file name cannot hold
+        assert_ne!(upload("a/b c").storage_key(), upload("a_b_c").storage_key());
+        // Separators inside the bucket or path
+        let split = |bucket, path| upload_to(bucket, path).storage_key();
+        assert_ne!(split("a-b", "c"), split("a", "b-c"));
+        assert_ne!(split("a.b", "c"), split("a", "b.c"));
+    }
+
+    #[test]
+    fn test_upload_metadata() {
+        let upload = upload("a/b.png").options(FileOptions {
+            content_type: Some("image/png".to_string()),
+            cache_control: Some(60),
+            upsert: true,
+        });
+        assert_eq!(
+            upload.metadata(),
+            "bucketName YmFja3Vwcw==,objectName YS9iLnBuZw==,\
+             contentType aW1hZ2UvcG5n,cacheControl NjA="
+        );
+    }
+
+    #[test]
+    fn test_upload_offset() {
+        let mut headers = HeaderMap::new();
+        assert!(upload_offset(&headers).is_err());
+
+        headers.insert("Upload-Offset", HeaderValue::from_static("6291456"));
+        assert_eq!(upload_offset(&headers).unwrap(), 6_291_456);
+
+        headers.insert("Upload-Offset", HeaderValue::from_static("-1"));
+        assert!(upload_offset(&headers).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Streaming uploads and downloads.
+
+use super::{
+    FileOptions, StorageClient, UploadResponse, check_response, handle_response, with_file_options,
+};
+use crate::error::{Error, Result};
+use crate::stream::ByteStream;
+use futures::TryStreamExt;
+use reqwest::header::{CONTENT_LENGTH, RANGE};
+use std::ops::{Bound, RangeBounds};
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
+
+impl StorageClient {
+    /// Upload `len` bytes read from `reader` without buffering the file.
+    ///
+    /// Returns the object key (`<bucket>/<path>`). The configured
+    /// [`timeout`](crate::SupabaseConfig::timeout) covers the whole
+    /// transfer, so raise it for large files.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use supabase_client_rs::storage::FileOptions;
+    ///
+    /// let file = tokio::fs::File::open("talk.mp4").await?;
+    /// let len = file.metadata().await?.len();
+    /// let options = FileOptions {
+    ///     content_type: Some("video/mp4".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// client
+    ///     .storage()
+    ///     .upload_stream("videos", "talk.mp4", file, len, &options)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_stream<R>(
+        &self,
+        bucket: &str,
+        path: &str,
+        reader: R,
+        len: u64,
+        options: &FileOptions,
+    ) -> Result<String>
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        let body = reqwest::Body::wrap_stream(ReaderStream::new(reader));
+        let request = self.http().post(self.url("object", bucket, path)?);
+        let response = with_file_options(request, options)
+            .header(CONTENT_LENGTH, len)
+            .body(body)
+            .send()
+            .await?;
+        let uploaded: UploadResponse = handle_response(response).await?;
+        Ok(uploaded.key)
+    }
+
+    /// Download a file as a stream of chunks.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use futures::TryStreamExt;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// let mut body = client.storage().download_stream("videos", "talk.mp4").await?;
+    /// let mut file = tokio::fs::File::create("talk.mp4").await?;
+    /// while let Some(chunk) = body.try_next().await? {
+    ///     file.write_all(&chunk).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_stream(&self, bucket: &str, path: &str) -> Result<ByteStream> {
+        let response = self
+            .http()
+            .get(self.url("object", bucket, path)?)
+            .send()
+            .await?;
+        Ok(body_stream(check_response(response).await?))
+    }
+
+    /// Download part of a file as a stream of chunks.
+    ///
+    /// `range` is in bytes, e.g. `0..1024` or `1024..` to skip the first KiB.
+    pub async fn download_range(
+        &self,
+        bucket: &str,
+        path: &str,
+        range: impl RangeBounds<u64>,
+    ) -> Result<ByteStream> {
+        let response = self
+            .http()
+            .get(self.url("object", bucket, path)?)
+            .header(RANGE, range_header(&range)?)
+            .send()
+            .await?;
+        Ok(body_stream(check_response(response).await?))
+    }
+}
+
+fn body_stream(response: reqwest::Response) -> ByteStream {
+    Box::pin(response.bytes_stream().map_err(Error::from))
+}
+
+/// Format a byte range as an HTTP `Range` header value.
+fn range_header(range: &impl RangeBounds<u64>) -> Result<String> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => Some(end),
+        Bound::Excluded(&end) if end > start => Some(end - 1),
+        Bound::Excluded(_) => return Err(Error::config("empty byte range")),
+        Bound::Unbounded => None,
+    };
+    Ok(match end {
+        Some(end) if end < start => return Err(Error::config("empty byte range")),
+        Some(end) => format!("bytes={}-{}", start, end),
+        None => format!("bytes={}-", start),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_header() {
+        assert_eq!(range_header(&(0..1024)).unwrap(), "bytes=0-1023");
+        assert_eq!(range_header(&(1024..)).unwrap(), "bytes=1024-");
+        assert_eq!(range_header(&(10..=10)).unwrap(), "bytes=10-10");
+        assert_eq!(range_header(&(..=99)).unwrap(), "bytes=0-99");
+        assert!(range_header(&(5..5)).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! Image transformations.
+
+use super::{SignedUrlResponse, StorageClient, check_response, handle_response};
+use crate::error::Result;
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+
+/// How an image is fitted into the requested width and height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resize {
+    /// Fill the box and crop the overflow (default)
+    Cover,
+    /// Fit inside the box, keeping the aspect ratio
+    Contain,
+    /// Stretch to the box, ignoring the aspect ratio
+    Fill,
+}
+
+/// Output format of a transformed image.
+///
+/// By default Storage picks WebP for clients that accept it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ImageFormat {
+    /// Keep the format of the original image
+    Origin,
+}
+
+/// Resize and re-encode an image served from Storage.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use supabase_client_rs::SupabaseClient;
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = SupabaseClient::new("url", "key")?;
+/// use supabase_client_rs::storage::{Resize, TransformOptions};
+///
+/// let thumbnail = TransformOptions {
+///     width: Some(200),
+///     height: Some(200),
+///     resize: Some(Resize::Cover),
+///     quality: Some(70),
+///     ..Default::default()
+/// };
+/// let url = client
+///     .storage()
+///     .get_public_url_with_transform("avatars", "alice.png", &thumbnail);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TransformOptions {
+    /// Width in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// Height in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Resize mode when both width and height are set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resize: Option<Resize>,
+    /// Quality from 20 to 100 (default: 80)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u8>,
+    /// Output format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ImageFormat>,
+}
+
+impl TransformOptions {
+    /// The options as query parameters, named and encoded like the JSON body
+    /// sent when signing.
+    fn query_pairs(&self) -> Vec<(String, String)> {
+        let Ok(Value::Object(options)) = serde_json::to_value(self) else {
+            return Vec::new();
+        };
+        options
+            .into_iter()
+            .map(|(name, value)| match value {
+                Value::String(value) => (name, value),
+                value => (name, value.to_string()),
+            })
+            .collect()
+    }
+
+    /// Whether no option is set, i.e. the image is served unchanged.
+    fn is_empty(&self) -> bool {
+        self.query_pairs().is_empty()
+    }
+
+    /// Append the options to the query string of `url`.
+    pub(crate) fn apply(&self, url: &mut Url) {
+        let pairs = self.query_pairs();
+        if !pairs.is_empty() {
+            url.query_pairs_mut().extend_pairs(pairs);
+        }
+    }
+
+    /// Body of the `object/sign` request for a transformed image.
+    fn sign_body(&self, expires_in: u64) -> Value {
+        serde_json::json!({ "expiresIn": expires_in, "transform": self })
+    }
+}
+
+impl StorageClient {
+    /// Get the URL of a transformed image in a public bucket.
+    ///
+    /// No request is made; the URL only works if the bucket is public.
+    /// Without any option set this is the plain
+    /// [`get_public_url`](Self::get_public_url).
+    pub fn get_public_url_with_transform(
+        &self,
+        bucket: &str,
+        path: &str,
+        transform: &TransformOptions,
+    ) -> String {
+        if transform.is_empty() {
+            return self.get_public_url(bucket, path);
+        }
+        match self.url("render/image/public", bucket, path) {
+            Ok(mut url) => {
+                transform.apply(&mut url);
+                url.into()
+            }
+            Err(_) => format!("{}/render/image/public/{}/{}", self.url, bucket, path),
+        }
+    }
+
+    /// Create a URL granting access to a transformed image for
+    /// `expires_in` seconds.
+    pub async fn create_signed_url_with_transform(
+        &self,
+        bucket: &str,
+        path: &str,
+        expires_in: u64,
+        transform: &TransformOptions,
+    ) -> Result<String> {
+        let response = self
+            .http()
+            .post(self.url("object/sign", bucket, path)?)
+            .json(&transform.sign_body(expires_in))
+            .send()
+            .await?;
+        let signed: SignedUrlResponse = handle_response(response).await?;
+        Ok(format!("{}{}", self.url, signed.signed_url))
+    }
+
+    /// Download a transformed image.
+    ///
+    /// Without any option set this is the plain [`download`](Self::download).
+    pub async fn download_with_transform(
+        &self,
+        bucket: &str,
+        path: &str,
+        transform: &TransformOptions,
+    ) -> Result<Vec<u8>> {
+        if transform.is_empty() {
+            return self.download(bucket, path).await;
+        }
+        let mut url = self.url("render/image/authenticated", bucket, path)?;
+        transform.apply(&mut url);
+        let response = self.http().get(url).send().await?;
+        Ok(check_response(response).await?.bytes().await?.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_body() {
+        let options = TransformOptions {
+            width: Some(200),
+            resize: Some(Resize::Cover),
+            format: Some(ImageFormat::Origin),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.sign_body(3600),
+            serde_json::json!({
+                "expiresIn": 3600,
+                "transform": { "width": 200, "resize": "cover", "format": "origin" },
+            })
+        );
+        assert_eq!(
+            TransformOptions::default().sign_body(60),
+            serde_json::json!({ "expiresIn": 60, "transform": {} })
+        );
+    }
+
+    #[test]
+    fn test_apply_without_options() {
+        let mut url = Url::parse("https://example.supabase.co/a.png").unwrap();
+        TransformOptions::default().apply(&mut url);
+        assert_eq!(url.as_str(), "https://example.supabase.co/a.png");
+    }
+}
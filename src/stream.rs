@@ -0,0 +1,277 @@
+//! Streaming pagination and raw response bodies for large result sets.
+
+use crate::error::{Error, Result};
+use crate::query::QueryBuilder;
+use bytes::Bytes;
+use futures::future::Either;
+use futures::stream::{self, Stream, TryStreamExt};
+use reqwest::header::{ACCEPT, HeaderValue, RANGE};
+use serde::de::DeserializeOwned;
+use std::pin::Pin;
+
+/// A response body streamed in chunks.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+impl QueryBuilder {
+    /// Send the request and stream the result as CSV (`text/csv`).
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use futures::TryStreamExt;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// let mut csv = client.from("orders").select("*").csv().await?;
+    /// let mut file = tokio::fs::File::create("orders.csv").await?;
+    /// while let Some(chunk) = csv.try_next().await? {
+    ///     file.write_all(&chunk).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn csv(self) -> Result<ByteStream> {
+        self.execute_stream("text/csv").await
+    }
+
+    /// Send the request and stream the result as a GeoJSON feature
+    /// collection (`application/geo+json`).
+    ///
+    /// The table or function result needs a PostGIS `geometry` or
+    /// `geography` column.
+    pub async fn geojson(self) -> Result<ByteStream> {
+        self.execute_stream("application/geo+json").await
+    }
+
+    /// Send the request and stream a single `bytea` value
+    /// (`application/octet-stream`).
+    ///
+    /// Select exactly one `bytea` column of exactly one row, or call a
+    /// function returning `bytea`.
+    pub async fn binary(self) -> Result<ByteStream> {
+        self.execute_stream("application/octet-stream").await
+    }
+
+    async fn execute_stream(self, accept: &'static str) -> Result<ByteStream> {
+        let response = self
+            .header(ACCEPT, HeaderValue::from_static(accept))
+            .execute()
+            .await?;
+        Ok(Box::pin(response.bytes_stream().map_err(Error::from)))
+    }
+
+    /// Stream rows page by page using offset pagination.
+    ///
+    /// At most `page_size` rows are held in memory at a time. Add an
+    /// `order()` to get a stable order across pages.
+    ///
+    /// Pages are requested with their own range, so the query must not have
+    /// a `limit()` or `range()`; the stream yields an error if it does.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut rows = std::pin::pin!(client
+    ///     .from("events")
+    ///     .select("*")
+    ///     .order("id")
+    ///     .stream_pages::<serde_json::Value>(1000));
+    ///
+    /// while let Some(row) = rows.try_next().await? {
+    ///     println!("{}", row);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_pages<T: DeserializeOwned>(
+        self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<T>> {
+        let page_size = page_size.max(1);
+        if let Err(e) = self.check_paging(false) {
+            return Either::Left(stream::once(async { Err(e) }));
+        }
+
+        let pages = stream::try_unfold(Some(0usize), move |offset| {
+            let query = self.clone();
+            async move {
+                let Some(offset) = offset else {
+                    return Ok(None);
+                };
+                let rows: Vec<T> = query
+                    .range(offset, offset + page_size - 1)
+                    .execute_as()
+                    .await?;
+                let next = next_offset(offset, page_size, rows.len());
+                Ok(Some((rows, next)))
+            }
+        });
+        Either::Right(flatten(pages))
+    }
+
+    /// Stream rows page by page using keyset (cursor) pagination.
+    ///
+    /// Rows are ordered ascending by `column` and each page continues after
+    /// the last value seen, so large offsets never have to be scanned.
+    /// `column` must be unique and non-null, and must be part of the
+    /// selected columns.
+    ///
+    /// The order and page size are set by this method, so the query must not
+    /// have an `order()`, `limit()` or `range()`; the stream yields an error
+    /// if it does.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use supabase_client_rs::SupabaseClient;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = SupabaseClient::new("url", "key")?;
+    /// use futures::TryStreamExt;
+    ///
+    /// let rows: Vec<serde_json::Value> = client
+    ///     .from("events")
+    ///     .select("id, payload")
+    ///     .stream_pages_by_key("id", 1000)
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_pages_by_key<T: DeserializeOwned>(
+        self,
+        column: &str,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<T>> {
+        let page_size = page_size.max(1);
+        if let Err(e) = self.check_paging(true) {
+            return Either::Left(stream::once(async { Err(e) }));
+        }
+        let column = column.to_string();
+        let base = self.order(format!("{}.asc", column));
+
+        let pages = stream::try_unfold(Some(None::<String>), move |cursor| {
+            let mut query = base.clone();
+            let column = column.clone();
+            async move {
+                let Some(cursor) = cursor else {
+                    return Ok(None);
+                };
+                if let Some(ref last) = cursor {
+                    query = query.gt(&column, last);
+                }
+                let rows: Vec<serde_json::Value> = query.limit(page_size).execute_as().await?;
+
+                let next = next_cursor(&rows, page_size, &column)?.map(Some);
+                let rows = rows
+                    .into_iter()
+                    .map(serde_json::from_value)
+                    .collect::<std::result::Result<Vec<T>, _>>()?;
+                Ok(Some((rows, next)))
+            }
+        });
+        Either::Right(flatten(pages))
+    }
+
+    /// Reject settings the paginated streams would override.
+    fn check_paging(&self, keyset: bool) -> Result<()> {
+        let request = self.clone().build()?;
+        let has_param = |name: &str| request.url().query_pairs().any(|(key, _)| key == name);
+
+        if request.headers().contains_key(RANGE) || has_param("limit") || has_param("offset") {
+            return Err(Error::config(
+                "paginated streams set their own range; remove limit() and range()",
+            ));
+        }
+        if keyset && has_param("order") {
+            return Err(Error::config(
+                "keyset pagination orders by its key column; remove order()",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Turn a stream of pages into a stream of rows.
+fn flatten<T>(pages: impl Stream<Item = Result<Vec<T>>>) -> impl Stream<Item = Result<T>> {
+    pages
+        .map_ok(|rows| stream::iter(rows.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+/// Offset of the next page, or `None` after a short (last) page.
+fn next_offset(offset: usize, page_size: usize, rows: usize) -> Option<usize> {
+    (rows == page_size).then_some(offset + page_size)
+}
+
+/// Cursor for the page after `rows`, or `None` after a short (last) page.
+fn next_cursor(
+    rows: &[serde_json::Value],
+    page_size: usize,
+    column: &str,
+) -> Result<Option<String>> {
+    match rows.last() {
+        Some(last) if rows.len() == page_size => cursor_value(last, column).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Read the keyset cursor from the last row of a page.
+fn cursor_value(row: &serde_json::Value, column: &str) -> Result<String> {
+    match row.get(column) {
+        Some(serde_json::Value::String(value)) => Ok(value.clone()),
+        Some(serde_json::Value::Null) | None => Err(Error::config(format!(
+            "keyset column `{}` is missing or null in the result",
+            column
+        ))),
+        Some(value) => Ok(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SupabaseClient;
+    use serde_json::json;
+
+    #[test]
+    fn test_cursor_value() {
+        assert_eq!(cursor_value(&json!({ "id": 42 }), "id").unwrap(), "42");
+        assert_eq!(
+            cursor_value(&json!({ "id": "b7e1" }), "id").unwrap(),
+            "b7e1"
+        );
+        assert!(cursor_value(&json!({ "id": null }), "id").is_err());
+        assert!(cursor_value(&json!({ "name": "x" }), "id").is_err());
+    }
+
+    #[test]
+    fn test_page_stop_conditions() {
+        assert_eq!(next_offset(0, 100, 100), Some(100));
+        assert_eq!(next_offset(100, 100, 37), None);
+        assert_eq!(next_offset(200, 100, 0), None);
+
+        let full = [json!({ "id": 1 }), json!({ "id": 2 })];
+        assert_eq!(next_cursor(&full, 2, "id").unwrap().as_deref(), Some("2"));
+        assert_eq!(next_cursor(&full[..1], 2, "id").unwrap(), None);
+        assert_eq!(next_cursor(&[], 2, "id").unwrap(), None);
+    }
+
+    #[test]
+    fn test_paging_rejects_conflicting_modifiers() {
+        let client = SupabaseClient::new("https://example.supabase.co", "key").unwrap();
+        let query = || client.from("events").select("*");
+
+        assert!(query().order("id").check_paging(false).is_ok());
+        assert!(query().check_paging(true).is_ok());
+        assert!(query().limit(10).check_paging(false).is_err());
+        assert!(query().range(0, 9).check_paging(true).is_err());
+        assert!(query().order("created_at").check_paging(true).is_err());
+    }
+}
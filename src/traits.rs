@@ -41,6 +41,26 @@ pub trait AuthProvider: Send + Sync {
     async fn refresh_session(&self) -> Result<Self::Session>;
 }
 
+/// Session storage trait.
+///
+/// Implement this trait to control where the auth layer persists sessions
+/// when [`SupabaseConfig::persist_session`](crate::SupabaseConfig::persist_session)
+/// is enabled, and where resumable Storage uploads remember their upload
+/// URLs. Values are opaque strings keyed by a per-project storage key.
+/// [`MemoryStorage`](crate::MemoryStorage) and
+/// [`FileStorage`](crate::FileStorage) are built in.
+#[async_trait]
+pub trait SessionStorage: Send + Sync + std::fmt::Debug {
+    /// Read the value stored under `key`.
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+
+    /// Store `value` under `key`, replacing any previous value.
+    async fn set(&self, key: &str, value: &str) -> Result<()>;
+
+    /// Remove the value stored under `key`.
+    async fn remove(&self, key: &str) -> Result<()>;
+}
+
 /// Storage provider trait.
 ///
 /// Implement this trait to provide file storage functionality.
@@ -102,3 +122,36 @@ pub trait FunctionsProvider: Send + Sync {
     where
         T: Serialize + Send + Sync;
 }
+
+/// A struct that maps to a database table.
+///
+/// Usually derived with `#[derive(SupabaseTable)]` (requires the `derive`
+/// feature), which also generates a constant per column. Use it with
+/// [`SupabaseClient::table`](crate::SupabaseClient::table).
+///
+/// # Example
+///
+/// ```rust
+/// use supabase_client_rs::SupabaseTable;
+///
+/// struct User {
+///     id: i64,
+///     email: String,
+/// }
+///
+/// impl SupabaseTable for User {
+///     const TABLE: &'static str = "users";
+///     const COLUMNS: &'static [&'static str] = &["id", "email"];
+///     const PRIMARY_KEY: &'static [&'static str] = &["id"];
+/// }
+/// ```
+pub trait SupabaseTable {
+    /// The table name.
+    const TABLE: &'static str;
+
+    /// The columns mapped to fields, in declaration order.
+    const COLUMNS: &'static [&'static str];
+
+    /// The primary key columns.
+    const PRIMARY_KEY: &'static [&'static str];
+}
@@ -0,0 +1,301 @@
+//! Derive macros for [`supabase-client-rs`](https://crates.io/crates/supabase-client-rs).
+//!
+//! Use them through the `derive` feature of `supabase-client-rs` rather than
+//! depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Ident, LitStr, Token, parse_macro_input};
+
+/// Derive `SupabaseTable` for a struct with named fields.
+///
+/// Generates the table name, the column list, the primary key and a
+/// `SCREAMING_CASE` constant per column.
+///
+/// # Attributes
+///
+/// - `#[supabase(table = "name")]` on the struct: table name (default: the
+///   struct name in snake_case)
+/// - `#[supabase(primary_key)]` on fields: part of the primary key (default:
+///   a field named `id`)
+/// - `#[supabase(rename = "name")]` on a field: column name (default: the
+///   field name)
+/// - `#[supabase(skip)]` on a field: not a column
+///
+/// Without `#[supabase(rename)]`, column names follow the field's
+/// `#[serde(rename)]` and the struct's `#[serde(rename_all)]`, so they match
+/// the keys serde reads and writes. Renames that differ between serializing
+/// and deserializing are rejected; set the column with `#[supabase(rename)]`.
+///
+/// Fields marked `#[serde(skip)]` or `#[serde(skip_deserializing)]` are not
+/// columns. `#[serde(flatten)]` is rejected unless the field also has
+/// `#[supabase(skip)]`.
+#[proc_macro_derive(SupabaseTable, attributes(supabase))]
+pub fn derive_supabase_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Column {
+    ident: Ident,
+    name: String,
+    primary_key: bool,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut table = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("supabase")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                table = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `table = \"...\"`"))
+            }
+        })?;
+    }
+    let table = table.unwrap_or_else(|| snake_case(&input.ident.to_string()));
+    let rename_all = serde_name(&input.attrs, "rename_all")?
+        .map(|rule| rule.resolve())
+        .transpose()?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "SupabaseTable requires named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "SupabaseTable can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut columns = Vec::new();
+    for field in fields {
+        let ident = field.ident.clone().expect("named field");
+        let mut name = None;
+        let mut primary_key = false;
+        let mut skip = false;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("supabase")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("primary_key") {
+                    primary_key = true;
+                } else if meta.path.is_ident("rename") {
+                    name = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                } else {
+                    return Err(meta.error("expected `primary_key`, `rename` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+        if skip || serde_skips(&field.attrs)? {
+            continue;
+        }
+        let name = match name {
+            Some(name) => name,
+            None => match serde_name(&field.attrs, "rename")? {
+                Some(rename) => rename.resolve()?.value(),
+                None => {
+                    let name = ident.to_string().trim_start_matches("r#").to_string();
+                    match &rename_all {
+                        Some(rule) => rename_field(&name, rule)?,
+                        None => name,
+                    }
+                }
+            },
+        };
+        columns.push(Column {
+            ident,
+            name,
+            primary_key,
+        });
+    }
+
+    let mut primary_key: Vec<&str> = columns
+        .iter()
+        .filter(|c| c.primary_key)
+        .map(|c| c.name.as_str())
+        .collect();
+    if primary_key.is_empty() && columns.iter().any(|c| c.name == "id") {
+        primary_key.push("id");
+    }
+
+    let names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    let constants = columns.iter().map(|c| {
+        let constant = Ident::new(
+            &c.ident.to_string().trim_start_matches("r#").to_uppercase(),
+            Span::call_site(),
+        );
+        let name = &c.name;
+        let doc = format!("The `{}` column.", name);
+        quote! {
+            #[doc = #doc]
+            pub const #constant: &'static str = #name;
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::supabase_client_rs::SupabaseTable for #ident #ty_generics #where_clause {
+            const TABLE: &'static str = #table;
+            const COLUMNS: &'static [&'static str] = &[#(#names),*];
+            const PRIMARY_KEY: &'static [&'static str] = &[#(#primary_key),*];
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #(#constants)*
+        }
+    })
+}
+
+/// A serde `rename`/`rename_all` value, possibly split by direction.
+struct SerdeName {
+    serialize: Option<LitStr>,
+    deserialize: Option<LitStr>,
+    attr: Attribute,
+}
+
+impl SerdeName {
+    /// The name used in both directions.
+    fn resolve(self) -> syn::Result<LitStr> {
+        match (self.serialize, self.deserialize) {
+            (Some(ser), Some(de)) if ser.value() == de.value() => Ok(ser),
+            _ => Err(Error::new_spanned(
+                self.attr,
+                "serde renames this differently for serializing and deserializing; \
+                 set the column name with `#[supabase(rename = \"...\")]`",
+            )),
+        }
+    }
+}
+
+/// Read `#[serde(<key> = "...")]` or `#[serde(<key>(serialize = "...", deserialize = "..."))]`.
+fn serde_name(attrs: &[Attribute], key: &str) -> syn::Result<Option<SerdeName>> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident(key) {
+                return skip_meta(meta);
+            }
+            let mut name = SerdeName {
+                serialize: None,
+                deserialize: None,
+                attr: attr.clone(),
+            };
+            if meta.input.peek(Token![=]) {
+                let value = meta.value()?.parse::<LitStr>()?;
+                name.serialize = Some(value.clone());
+                name.deserialize = Some(value);
+            } else {
+                meta.parse_nested_meta(|direction| {
+                    let value = direction.value()?.parse::<LitStr>()?;
+                    if direction.path.is_ident("serialize") {
+                        name.serialize = Some(value);
+                    } else if direction.path.is_ident("deserialize") {
+                        name.deserialize = Some(value);
+                    }
+                    Ok(())
+                })?;
+            }
+            found = Some(name);
+            Ok(())
+        })?;
+    }
+    Ok(found)
+}
+
+/// Whether serde never reads the field from a row.
+///
+/// `skip` and `skip_deserializing` fields are left out of the columns.
+/// `flatten` spreads a nested struct over several columns the derive cannot
+/// see, so it is rejected.
+fn serde_skips(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut skip = false;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                skip = true;
+            } else if meta.path.is_ident("flatten") {
+                return Err(meta.error(
+                    "`#[serde(flatten)]` fields are not columns; add \
+                     `#[supabase(skip)]` and list the flattened columns as fields",
+                ));
+            }
+            skip_meta(meta)
+        })?;
+    }
+    Ok(skip)
+}
+
+/// Consume a serde option that does not affect column names.
+fn skip_meta(meta: ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if !meta.input.is_empty() && !meta.input.peek(Token![,]) {
+        meta.parse_nested_meta(skip_meta)?;
+    }
+    Ok(())
+}
+
+/// Apply a serde `rename_all` rule to a `snake_case` field name.
+fn rename_field(name: &str, rule: &LitStr) -> syn::Result<String> {
+    let pascal = || -> String {
+        name.split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect()
+    };
+    Ok(match rule.value().as_str() {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => pascal,
+            }
+        }
+        "snake_case" => name.to_string(),
+        "SCREAMING_SNAKE_CASE" => name.to_uppercase(),
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.to_uppercase().replace('_', "-"),
+        _ => return Err(Error::new_spanned(rule, "unknown serde `rename_all` rule")),
+    })
+}
+
+/// Convert a `CamelCase` type name to `snake_case`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(ch.to_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}